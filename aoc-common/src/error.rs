@@ -0,0 +1,124 @@
+// Errors from solving a puzzle, mostly due to bad input.
+
+use std::error;
+use std::fmt;
+use std::result;
+
+// Input that couldn't be parsed, with enough detail to find and fix
+// the problem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    // The solvers only see the input text, so the file is filled in
+    // later by whoever read it.
+    pub file: Option<String>,
+    // Both 1-based, like an editor.
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    // An error at the given position, where "found" describes what's
+    // actually there.
+    pub fn new(line: usize, column: usize, expected: &str, found: &str) -> ParseError {
+        ParseError {
+            file: None,
+            line,
+            column,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.file {
+            Some(ref file) => write!(f, "{}:{}:{}", file, self.line, self.column)?,
+            None => write!(f, "line {}, column {}", self.line, self.column)?,
+        }
+        write!(f, ": expected {}, found {}", self.expected, self.found)
+    }
+}
+
+impl error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Parse(ParseError),
+    // Input that parses, but doesn't make sense or has no solution.
+    Invalid(String),
+}
+
+impl Error {
+    pub fn invalid<S: Into<String>>(msg: S) -> Error {
+        Error::Invalid(msg.into())
+    }
+
+    // Attach the name of the input file to the error.
+    pub fn with_file(self, file: &str) -> Error {
+        match self {
+            Error::Parse(e) => Error::Parse(ParseError {
+                file: Some(file.to_string()),
+                ..e
+            }),
+            Error::Invalid(msg) => Error::Invalid(format!("{}: {}", file, msg)),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Parse(e) => e.fmt(f),
+            Error::Invalid(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Error {
+        Error::Parse(e)
+    }
+}
+
+pub type Result<T> = result::Result<T, Error>;
+pub type ParseResult<T> = result::Result<T, ParseError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ParseError {
+        ParseError {
+            file: None,
+            line: 3,
+            column: 7,
+            expected: "a number".to_string(),
+            found: "\"x\"".to_string(),
+        }
+    }
+
+    #[test]
+    fn display_without_file() {
+        assert_eq!(
+            Error::from(sample()).to_string(),
+            "line 3, column 7: expected a number, found \"x\""
+        );
+    }
+
+    #[test]
+    fn display_with_file() {
+        assert_eq!(
+            Error::from(sample()).with_file("input.txt").to_string(),
+            "input.txt:3:7: expected a number, found \"x\""
+        );
+        assert_eq!(
+            Error::invalid("No solution").with_file("input.txt").to_string(),
+            "input.txt: No solution"
+        );
+    }
+}
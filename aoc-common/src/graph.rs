@@ -0,0 +1,121 @@
+// Dependency graphs, given as the set of nodes each node depends on,
+// as in day 7.
+
+use std::collections::{BTreeMap, HashSet};
+use std::hash::Hash;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Visiting,
+    Done,
+}
+
+// What a node depends on, sorted so that the cycle found doesn't
+// depend on hash order, and backwards so that they pop off in order.
+fn sorted_deps<'a, T: Ord + Hash>(deps: &'a BTreeMap<T, HashSet<T>>, node: &T) -> Vec<&'a T> {
+    let mut node_deps = deps.get(node).into_iter().flatten().collect::<Vec<_>>();
+    node_deps.sort_by(|a, b| b.cmp(a));
+    node_deps
+}
+
+// Search depth-first from "start", returning the cycle if it gets back
+// to a node that's still being visited. The stack holds the chain of
+// nodes being visited, each depending on the one after, with what each
+// has left to visit. It's kept by hand, as chains of dependencies can
+// be far deeper than the call stack.
+fn visit<'a, T: Ord + Hash + Clone>(
+    deps: &'a BTreeMap<T, HashSet<T>>,
+    start: &'a T,
+    states: &mut BTreeMap<T, State>,
+) -> Option<Vec<T>> {
+    if states.contains_key(start) {
+        return None;
+    }
+    states.insert(start.clone(), State::Visiting);
+    let mut stack = vec![(start, sorted_deps(deps, start))];
+    while !stack.is_empty() {
+        let next = stack.last_mut().unwrap().1.pop();
+        match next {
+            None => {
+                let (node, _) = stack.pop().unwrap();
+                states.insert(node.clone(), State::Done);
+            }
+            Some(dep) => match states.get(dep) {
+                Some(State::Done) => (),
+                Some(State::Visiting) => {
+                    let start = stack.iter().position(|&(n, _)| n == dep).unwrap();
+                    return Some(stack[start..].iter().map(|&(n, _)| n.clone()).collect());
+                }
+                None => {
+                    states.insert(dep.clone(), State::Visiting);
+                    stack.push((dep, sorted_deps(deps, dep)));
+                }
+            },
+        }
+    }
+    None
+}
+
+// A cycle of dependencies, if there is one. It's given in the order
+// the nodes would have to be done in, so each must be done before the
+// one after it, and the last before the first, starting from the
+// smallest.
+pub fn find_cycle<T: Ord + Hash + Clone>(deps: &BTreeMap<T, HashSet<T>>) -> Option<Vec<T>> {
+    let mut states = BTreeMap::new();
+    for node in deps.keys() {
+        if let Some(mut cycle) = visit(deps, node, &mut states) {
+            cycle.reverse();
+            let smallest = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap();
+            cycle.rotate_left(smallest);
+            return Some(cycle);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(char, char)]) -> BTreeMap<char, HashSet<char>> {
+        let mut deps = BTreeMap::new();
+        for &(before, after) in edges.iter() {
+            deps.entry(before).or_insert_with(HashSet::new);
+            deps.entry(after).or_insert_with(HashSet::new).insert(before);
+        }
+        deps
+    }
+
+    #[test]
+    fn no_cycle() {
+        let deps = graph(&[('C', 'A'), ('C', 'F'), ('A', 'B'), ('A', 'D'), ('B', 'E'), ('D', 'E'), ('F', 'E')]);
+        assert_eq!(find_cycle(&deps), None);
+    }
+
+    #[test]
+    fn finds_cycle() {
+        // B before C before D before B, with A leading into it.
+        let deps = graph(&[('A', 'B'), ('B', 'C'), ('C', 'D'), ('D', 'B'), ('D', 'E')]);
+        assert_eq!(find_cycle(&deps), Some(vec!['B', 'C', 'D']));
+    }
+
+    #[test]
+    fn copes_with_long_chains() {
+        // Each node depends on the one before, and then the first on
+        // the last.
+        let n = 1_000_000;
+        let mut deps = (0..n).map(|i| (i, HashSet::new())).collect::<BTreeMap<u32, _>>();
+        for i in 1..n {
+            deps.get_mut(&i).unwrap().insert(i - 1);
+        }
+        assert_eq!(find_cycle(&deps), None);
+        deps.get_mut(&0).unwrap().insert(n - 1);
+        assert_eq!(find_cycle(&deps).map(|cycle| cycle.len()), Some(n as usize));
+    }
+
+    #[test]
+    fn finds_self_dependency() {
+        let deps = graph(&[('A', 'B'), ('B', 'B')]);
+        assert_eq!(find_cycle(&deps), Some(vec!['B']));
+    }
+}
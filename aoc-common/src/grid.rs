@@ -0,0 +1,323 @@
+// A rectangular 2D grid, for the days that are played out on a map.
+//
+// Cells are addressed by (x, y), with y increasing down the rows. The
+// top-left corner needn't be (0, 0), so puzzles whose coordinates
+// start somewhere else (e.g. day 17's x=500) can use theirs
+// directly. Coordinates are signed so that stepping off the top or
+// left edge gives an out-of-bounds position rather than an underflow.
+
+use std::ops::{Index, IndexMut};
+
+use error::ParseError;
+use scan;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    min_x: isize,
+    min_y: isize,
+    width: usize,
+    height: usize,
+    // Row-major.
+    cells: Vec<T>,
+}
+
+// Neighbour offsets, in reading order.
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+impl<T: Clone> Grid<T> {
+    // A grid with its top-left corner at (0, 0).
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid::with_origin(0, 0, width, height, fill)
+    }
+
+    pub fn with_origin(min_x: isize, min_y: isize, width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            min_x,
+            min_y,
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    // Like "parse", but rows may be ragged. The grid is as wide as the
+    // longest row, and shorter ones are padded with "fill".
+    pub fn parse_padded<F>(input: &str, expected: &str, fill: T, f: F) -> Result<Grid<T>, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        Grid::parse_rows(input, expected, f, Some(|| fill.clone()))
+    }
+}
+
+impl<T> Grid<T> {
+    // Parse a grid with one character per cell, converted by "f",
+    // which returns None for characters that aren't allowed. "expected"
+    // describes the allowed characters for the error message. Every
+    // row must be the same width, and the top-left is (0, 0).
+    pub fn parse<F>(input: &str, expected: &str, f: F) -> Result<Grid<T>, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        Grid::parse_rows(input, expected, f, None::<fn() -> T>)
+    }
+
+    fn parse_rows<F, P>(input: &str, expected: &str, mut f: F, pad: Option<P>) -> Result<Grid<T>, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+        P: Fn() -> T,
+    {
+        let mut lines = input.lines().collect::<Vec<_>>();
+        while lines.last() == Some(&"") {
+            lines.pop();
+        }
+        let width = match (lines.first(), &pad) {
+            (None, _) => return Err(scan::unexpected_end(input, "a grid")),
+            (Some(line), None) => line.chars().count(),
+            (Some(_), Some(_)) => lines.iter().map(|l| l.chars().count()).max().unwrap(),
+        };
+
+        let mut cells = Vec::new();
+        for (line, y) in lines.iter().zip(1..) {
+            let mut len = 0;
+            for (c, x) in line.chars().zip(1..) {
+                if x > width {
+                    let expected = format!("a row {} wide", width);
+                    return Err(ParseError::new(y, x, &expected, &format!("{:?}", c)));
+                }
+                match f(c) {
+                    Some(cell) => cells.push(cell),
+                    None => return Err(ParseError::new(y, x, expected, &format!("{:?}", c))),
+                }
+                len = x;
+            }
+            match pad {
+                Some(ref pad) => cells.extend((len..width).map(|_| pad())),
+                None if len < width => {
+                    let expected = format!("a row {} wide", width);
+                    return Err(ParseError::new(y, len + 1, &expected, "end of line"));
+                }
+                None => {}
+            }
+        }
+
+        Ok(Grid {
+            min_x: 0,
+            min_y: 0,
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn min_x(&self) -> isize {
+        self.min_x
+    }
+
+    pub fn min_y(&self) -> isize {
+        self.min_y
+    }
+
+    // Inclusive.
+    pub fn max_x(&self) -> isize {
+        self.min_x + self.width as isize - 1
+    }
+
+    // Inclusive.
+    pub fn max_y(&self) -> isize {
+        self.min_y + self.height as isize - 1
+    }
+
+    fn offset(&self, x: isize, y: isize) -> Option<usize> {
+        let (dx, dy) = (x - self.min_x, y - self.min_y);
+        if dx < 0 || dy < 0 || dx as usize >= self.width || dy as usize >= self.height {
+            return None;
+        }
+        Some(dy as usize * self.width + dx as usize)
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        self.offset(x, y).is_some()
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.offset(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        match self.offset(x, y) {
+            Some(i) => Some(&mut self.cells[i]),
+            None => None,
+        }
+    }
+
+    // Every position in the grid, in reading order. Doesn't borrow
+    // the grid, so it can be updated along the way.
+    pub fn positions(&self) -> impl Iterator<Item = (isize, isize)> {
+        let (min_x, min_y, width) = (self.min_x, self.min_y, self.width as isize);
+        (0..self.cells.len() as isize).map(move |i| (min_x + i % width, min_y + i / width))
+    }
+
+    // Every cell, in reading order.
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    // Every cell along with its position, in reading order.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = ((isize, isize), &'a T)> + 'a {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // The neighbours above, left, right and below that are in the
+    // grid, in reading order.
+    pub fn neighbours4<'a>(&'a self, x: isize, y: isize) -> impl Iterator<Item = (isize, isize)> + 'a {
+        self.neighbours(x, y, &NEIGHBOURS_4)
+    }
+
+    // All eight surrounding positions that are in the grid, in
+    // reading order.
+    pub fn neighbours8<'a>(&'a self, x: isize, y: isize) -> impl Iterator<Item = (isize, isize)> + 'a {
+        self.neighbours(x, y, &NEIGHBOURS_8)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        x: isize,
+        y: isize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (isize, isize)> + 'a {
+        offsets
+            .iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(move |(x, y)| self.contains(*x, *y))
+    }
+
+    // A grid of the same shape, with each cell converted by "f".
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            min_x: self.min_x,
+            min_y: self.min_y,
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    // Draw the grid with one character per cell, one line per row.
+    pub fn render<F>(&self, mut f: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        self.rows()
+            .map(|row| row.iter().map(&mut f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<(isize, isize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (isize, isize)) -> &T {
+        match self.offset(x, y) {
+            Some(i) => &self.cells[i],
+            None => panic!("({}, {}) is outside the grid", x, y),
+        }
+    }
+}
+
+impl<T> IndexMut<(isize, isize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (isize, isize)) -> &mut T {
+        match self.offset(x, y) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("({}, {}) is outside the grid", x, y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_chars(input: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse(input, "'#' or '.'", |c| if c == '#' || c == '.' { Some(c) } else { None })
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let grid = parse_chars("#..\n.#.\n\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], '#');
+        assert_eq!(grid.render(|c| if *c == '#' { 'X' } else { ' ' }), "X  \n X ");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let e = parse_chars("#..\n.x.\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.expected.as_str()), (2, 2, "'#' or '.'"));
+        let e = parse_chars("#..\n.#\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (2, 3, "end of line"));
+        let e = parse_chars("#..\n.#..\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.expected.as_str()), (2, 4, "a row 3 wide"));
+        assert!(parse_chars("").is_err());
+    }
+
+    #[test]
+    fn parse_padded() {
+        let grid = Grid::parse_padded("#\n.#.\n", "'#' or '.'", ' ', Some).unwrap();
+        assert_eq!(grid.render(|c| *c), "#  \n.#.");
+    }
+
+    #[test]
+    fn offset_origin() {
+        let mut grid = Grid::with_origin(498, -1, 3, 2, 0);
+        assert_eq!((grid.max_x(), grid.max_y()), (500, 0));
+        grid[(500, 0)] = 7;
+        assert_eq!(grid.get(500, 0), Some(&7));
+        assert_eq!(grid.get(497, 0), None);
+        assert_eq!(grid.get(498, 1), None);
+        assert_eq!(grid.positions().last(), Some((500, 0)));
+        assert_eq!(grid.iter().find(|(_, v)| **v == 7).map(|(p, _)| p), Some((500, 0)));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(grid.neighbours4(1, 1).collect::<Vec<_>>(), vec![(1, 0), (0, 1), (2, 1), (1, 2)]);
+        assert_eq!(grid.neighbours4(0, 0).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8(1, 1).count(), 8);
+        assert_eq!(grid.neighbours8(2, 2).collect::<Vec<_>>(), vec![(1, 1), (2, 1), (1, 2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the grid")]
+    fn index_out_of_bounds() {
+        let grid = Grid::new(2, 2, 0);
+        let _ = grid[(2, 0)];
+    }
+}
@@ -0,0 +1,11 @@
+// Bits and pieces shared between the days.
+
+pub mod error;
+pub mod graph;
+pub mod grid;
+pub mod ocr;
+pub mod scan;
+
+pub use error::{Error, ParseError, ParseResult, Result};
+pub use grid::Grid;
+pub use scan::Scanner;
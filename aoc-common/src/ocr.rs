@@ -0,0 +1,127 @@
+// Read the block letters that puzzles draw in '#' and '.', as in day
+// 10's message.
+//
+// Letters are told apart by the blank columns between them, so each
+// is matched on just its lit columns. Only the 10-row font of the real
+// inputs is known. The puzzle's example is drawn in an 8-row font, but
+// shows just H and I of it, which isn't enough to be worth having, so
+// its letters come back unrecognised.
+
+use error::{Error, Result};
+
+// The font of the real inputs: letters 6 wide and 10 tall, drawn 2
+// apart. These are the letters seen in puzzles so far.
+const FONT_10: &[(char, [&str; 10])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+fn lookup(glyph: &[String]) -> Option<char> {
+    FONT_10
+        .iter()
+        .find(|(_, rows)| rows.len() == glyph.len() && rows.iter().zip(glyph.iter()).all(|(a, b)| a == b))
+        .map(|(c, _)| *c)
+}
+
+// Split a drawing into letters at its blank columns. Each letter is
+// given as its rows, with the blank columns either side trimmed off.
+pub fn glyphs(bitmap: &str) -> Vec<Vec<String>> {
+    let rows = bitmap.lines().map(|row| row.chars().collect::<Vec<_>>()).collect::<Vec<_>>();
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&'#'));
+
+    let mut glyphs = Vec::new();
+    let mut x = 0;
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+        glyphs.push(
+            rows.iter()
+                .map(|row| (start..x).map(|i| if row.get(i) == Some(&'#') { '#' } else { '.' }).collect())
+                .collect(),
+        );
+    }
+    glyphs
+}
+
+// The text of a drawing, or an error showing each letter that isn't in
+// a font, numbered from 1.
+pub fn read(bitmap: &str) -> Result<String> {
+    let mut text = String::new();
+    let mut unknown = Vec::new();
+    for (i, glyph) in glyphs(bitmap).iter().enumerate() {
+        match lookup(glyph) {
+            Some(c) => text.push(c),
+            None => unknown.push(format!("Letter {}:\n{}", i + 1, glyph.join("\n"))),
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(Error::invalid(format!("Unrecognised letters in the message\n{}", unknown.join("\n"))));
+    }
+    if text.is_empty() {
+        return Err(Error::invalid("There are no letters in the message"));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Letters side by side, 2 apart.
+    fn draw(rows: &[&[&str]]) -> String {
+        let height = rows[0].len();
+        (0..height)
+            .map(|y| rows.iter().map(|glyph| glyph[y]).collect::<Vec<_>>().join(".."))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn reads_every_letter() {
+        let glyphs = FONT_10.iter().map(|(_, rows)| &rows[..]).collect::<Vec<_>>();
+        let expected = FONT_10.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(read(&draw(&glyphs)).unwrap(), expected);
+    }
+
+    #[test]
+    fn doesnt_read_example_font() {
+        let bitmap = "#...#..###\n#...#...#.\n#...#...#.\n#####...#.\n#...#...#.\n#...#...#.\n#...#...#.\n#...#..###";
+        let err = read(bitmap).unwrap_err().to_string();
+        assert!(err.contains("Letter 1:\n#...#\n"), "{}", err);
+        assert!(err.contains("Letter 2:\n###\n.#."), "{}", err);
+    }
+
+    #[test]
+    fn reports_unknown_letters() {
+        let h = FONT_10[6].1;
+        let blob = ["##", "##", "##", "##", "##", "##", "##", "##", "##", "##"];
+        let err = read(&draw(&[&h, &blob, &h])).unwrap_err().to_string();
+        assert!(err.contains("Letter 2:\n##\n##"), "{}", err);
+        assert!(!err.contains("Letter 1"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_blank_drawing() {
+        assert!(read("....\n....").is_err());
+    }
+}
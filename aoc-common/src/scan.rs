@@ -0,0 +1,279 @@
+// A simple scanner for picking apart a line of input, which keeps
+// track of where it is so that it can report helpful errors.
+//
+// The token-reading methods (literal, number, word) skip any
+// whitespace in front of the token, so "a, b" and "a,b" read the
+// same. Reading single characters doesn't skip anything.
+
+use std::str::FromStr;
+
+use error::ParseError;
+
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    text: &'a str,
+    // 1-based line number, for errors.
+    line: usize,
+    // Byte offset of the next character.
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(text: &'a str, line: usize) -> Scanner<'a> {
+        Scanner { text, line, pos: 0 }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    // 1-based column of the next character.
+    pub fn column(&self) -> usize {
+        self.text[..self.pos].chars().count() + 1
+    }
+
+    // Everything not yet read.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    // Describe what's next in the input, for an error message.
+    fn found(&self) -> String {
+        let rest = self.rest();
+        match rest.chars().next() {
+            None => "end of line".to_string(),
+            Some(c) if c.is_alphanumeric() => {
+                let end = rest.find(|c: char| !c.is_alphanumeric()).unwrap_or(rest.len());
+                format!("{:?}", &rest[..end])
+            }
+            Some(c) => format!("{:?}", c),
+        }
+    }
+
+    // An error at the current position.
+    pub fn error(&self, expected: &str) -> ParseError {
+        ParseError::new(self.line, self.column(), expected, &self.found())
+    }
+
+    // An error about something already read, starting at the given
+    // column.
+    pub fn error_at(&self, column: usize, expected: &str, found: &str) -> ParseError {
+        ParseError::new(self.line, column, expected, &format!("{:?}", found))
+    }
+
+    // Read the given text, which may be preceded by whitespace.
+    pub fn literal(&mut self, lit: &str) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        let lit = lit.trim_start();
+        if self.rest().starts_with(lit) {
+            self.pos += lit.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("{:?}", lit)))
+        }
+    }
+
+    // Read an optionally-signed decimal number.
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let rest = self.rest();
+        let sign_len = if rest.starts_with('-') || rest.starts_with('+') { 1 } else { 0 };
+        let digits = rest[sign_len..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign_len);
+        if digits == 0 {
+            return Err(self.error("a number"));
+        }
+        self.pos += sign_len + digits;
+        let token = &self.text[start..self.pos];
+        // Only work out the column on failure, as it means counting
+        // from the start of the line.
+        token
+            .parse()
+            .map_err(|_| self.error_at(self.text[..start].chars().count() + 1, "a number in range", token))
+    }
+
+    // Read a run of letters, digits and underscores.
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("a word"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    // Read a word, which must be one of the given options.
+    pub fn keyword<'b>(&mut self, options: &[&'b str]) -> Result<&'b str, ParseError> {
+        self.skip_whitespace();
+        let column = self.column();
+        let saved = self.pos;
+        let expected = || {
+            options
+                .iter()
+                .map(|o| format!("{:?}", o))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        };
+        let word = self.word().map_err(|_| self.error(&expected()))?;
+        match options.iter().find(|o| **o == word) {
+            Some(o) => Ok(o),
+            None => {
+                self.pos = saved;
+                Err(self.error_at(column, &expected(), word))
+            }
+        }
+    }
+
+    // Check there's nothing left but whitespace.
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.error("end of line"))
+        }
+    }
+}
+
+// Scanners for every line of the input, numbered from 1.
+pub fn lines<'a>(input: &'a str) -> impl Iterator<Item = Scanner<'a>> {
+    input.lines().zip(1..).map(|(l, n)| Scanner::new(l, n))
+}
+
+// Parse each non-blank line with the given function, which must
+// consume the whole line.
+pub fn parse_lines<'a, T, F>(input: &'a str, mut f: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&mut Scanner<'a>) -> Result<T, ParseError>,
+{
+    lines(input)
+        .filter(|s| !s.rest().trim().is_empty())
+        .map(|mut s| {
+            let res = f(&mut s)?;
+            s.end()?;
+            Ok(res)
+        }).collect()
+}
+
+// Parse input that should be a single non-blank line, described by
+// "what" if it's missing.
+pub fn parse_single<'a, T, F>(input: &'a str, what: &str, f: F) -> Result<T, ParseError>
+where
+    F: FnOnce(&mut Scanner<'a>) -> Result<T, ParseError>,
+{
+    let mut lines = lines(input).filter(|s| !s.rest().trim().is_empty());
+    let mut s = match lines.next() {
+        Some(s) => s,
+        None => return Err(unexpected_end(input, what)),
+    };
+    let res = f(&mut s)?;
+    s.end()?;
+    if let Some(mut extra) = lines.next() {
+        extra.skip_whitespace();
+        return Err(extra.error("end of input"));
+    }
+    Ok(res)
+}
+
+// An error for input that ran out early.
+pub fn unexpected_end(input: &str, expected: &str) -> ParseError {
+    ParseError::new(input.lines().count() + 1, 1, expected, "end of input")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_tokens() {
+        let mut s = Scanner::new("#12 @ -3,+4: 5x6 thing_1", 1);
+        s.literal("#").unwrap();
+        assert_eq!(s.number::<u32>().unwrap(), 12);
+        s.literal(" @ ").unwrap();
+        assert_eq!(s.number::<i32>().unwrap(), -3);
+        s.literal(",").unwrap();
+        assert_eq!(s.number::<i32>().unwrap(), 4);
+        s.literal(":").unwrap();
+        assert_eq!(s.number::<u32>().unwrap(), 5);
+        s.literal("x").unwrap();
+        assert_eq!(s.number::<u32>().unwrap(), 6);
+        assert_eq!(s.word().unwrap(), "thing_1");
+        s.end().unwrap();
+    }
+
+    #[test]
+    fn reports_position_and_token() {
+        let mut s = Scanner::new("x=49a", 7);
+        s.literal("x=").unwrap();
+        s.number::<u32>().unwrap();
+        let e = s.end().unwrap_err();
+        assert_eq!((e.line, e.column), (7, 5));
+        assert_eq!(e.expected, "end of line");
+        assert_eq!(e.found, "\"a\"");
+
+        let mut s = Scanner::new("  abc", 1);
+        let e = s.number::<u32>().unwrap_err();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (3, "a number", "\"abc\""));
+
+        let e = Scanner::new("", 1).literal("Step").unwrap_err();
+        assert_eq!((e.expected.as_str(), e.found.as_str()), ("\"Step\"", "end of line"));
+    }
+
+    #[test]
+    fn number_out_of_range() {
+        let e = Scanner::new(" 300", 1).number::<u8>().unwrap_err();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (2, "a number in range", "\"300\""));
+    }
+
+    #[test]
+    fn keywords() {
+        let mut s = Scanner::new("weak to fire", 1);
+        assert_eq!(s.keyword(&["weak", "immune"]).unwrap(), "weak");
+        let e = s.keyword(&["from", "by"]).unwrap_err();
+        assert_eq!(e.expected, "\"from\" or \"by\"");
+        assert_eq!(e.found, "\"to\"");
+        assert_eq!(e.column, 6);
+    }
+
+    #[test]
+    fn parses_lines() {
+        let res = parse_lines("1\n\n 2 \n", |s| s.number::<i32>()).unwrap();
+        assert_eq!(res, vec![1, 2]);
+        let e = parse_lines("1\n2 3\n", |s| s.number::<i32>()).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 3));
+    }
+
+    #[test]
+    fn parses_single_line() {
+        assert_eq!(parse_single("\n 42\n", "a number", |s| s.number::<i32>()), Ok(42));
+        let e = parse_single("1\n2\n", "a number", |s| s.number::<i32>()).unwrap_err();
+        assert_eq!((e.line, e.expected.as_str()), (2, "end of input"));
+        let e = parse_single("", "a number", |s| s.number::<i32>()).unwrap_err();
+        assert_eq!((e.line, e.expected.as_str(), e.found.as_str()), (1, "a number", "end of input"));
+    }
+}
@@ -0,0 +1,164 @@
+// Reader for answers.toml, the file of expected answers used by the
+// regression tests.
+//
+// It's only a tiny subset of TOML, as we've no dependencies to pull
+// in a real parser: "[section]" headers naming a solver, and
+// "key" = value pairs mapping an input file to the expected answer,
+// where the value is a basic "string" or a '''multi-line literal'''.
+// Comments start with '#'.
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use workspace_root;
+
+// Solver name to (input path, expected answer) pairs, in file order.
+pub type Answers = BTreeMap<String, Vec<(String, String)>>;
+
+// Parse a "quoted" string, returning it and the rest of the line.
+fn parse_string(s: &str) -> Result<(String, &str), String> {
+    if !s.starts_with('"') {
+        return Err(format!("Expected '\"', found {:?}", s));
+    }
+    let mut res = String::new();
+    let mut chars = s[1..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((res, &s[i + 2..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => res.push('\n'),
+                Some((_, 't')) => res.push('\t'),
+                Some((_, c @ '"')) | Some((_, c @ '\\')) => res.push(c),
+                Some((_, c)) => return Err(format!("Unknown escape: \\{}", c)),
+                None => break,
+            },
+            c => res.push(c),
+        }
+    }
+    Err("Unterminated string".to_string())
+}
+
+// Nothing but whitespace and an optional comment may end a line.
+fn check_end(s: &str) -> Result<(), String> {
+    let s = s.trim();
+    if s.is_empty() || s.starts_with('#') {
+        Ok(())
+    } else {
+        Err(format!("Unexpected trailing text: {:?}", s))
+    }
+}
+
+pub fn parse(text: &str) -> Result<Answers, String> {
+    let mut answers = Answers::new();
+    let mut section: Option<String> = None;
+    let mut lines = text.lines().enumerate();
+
+    while let Some((line_no, line)) = lines.next() {
+        let err = |msg: String| format!("line {}: {}", line_no + 1, msg);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            let end = line.find(']').ok_or_else(|| err("Expected ']'".to_string()))?;
+            check_end(&line[end + 1..]).map_err(err)?;
+            let name = line[1..end].trim().to_string();
+            if answers.contains_key(&name) {
+                return Err(err(format!("Duplicate section [{}]", name)));
+            }
+            answers.insert(name.clone(), Vec::new());
+            section = Some(name);
+            continue;
+        }
+
+        let name = section
+            .clone()
+            .ok_or_else(|| err("Key outside a section".to_string()))?;
+        let (key, rest) = parse_string(line).map_err(err)?;
+        let rest = rest.trim_start();
+        if !rest.starts_with('=') {
+            return Err(err(format!("Expected '=', found {:?}", rest)));
+        }
+        let rest = rest[1..].trim_start();
+
+        let value = if let Some(mut rest) = rest.strip_prefix("'''") {
+            // Multi-line literal. As in TOML, a newline straight after
+            // the opening quotes is dropped.
+            let mut value = String::new();
+            if rest.is_empty() {
+                rest = match lines.next() {
+                    Some((_, l)) => l,
+                    None => return Err(err("Unterminated ''' string".to_string())),
+                };
+            }
+            loop {
+                if let Some(end) = rest.find("'''") {
+                    value.push_str(&rest[..end]);
+                    check_end(&rest[end + 3..]).map_err(err)?;
+                    break;
+                }
+                value.push_str(rest);
+                value.push('\n');
+                rest = match lines.next() {
+                    Some((_, l)) => l,
+                    None => return Err(err("Unterminated ''' string".to_string())),
+                };
+            }
+            value
+        } else {
+            let (value, rest) = parse_string(rest).map_err(err)?;
+            check_end(rest).map_err(err)?;
+            value
+        };
+
+        answers.get_mut(&name).unwrap().push((key, value));
+    }
+
+    Ok(answers)
+}
+
+// Load answers.toml from the workspace root.
+pub fn load() -> Result<Answers, String> {
+    let path = workspace_root().join("answers.toml");
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sections_and_strings() {
+        let text = "# Comment\n[day01a]\n\"a.txt\" = \"1\" # Trailing\n\"b.txt\"=\"x\\\"y\"\n\n[day10a]\n";
+        let answers = parse(text).unwrap();
+        assert_eq!(
+            answers["day01a"],
+            vec![
+                ("a.txt".to_string(), "1".to_string()),
+                ("b.txt".to_string(), "x\"y".to_string()),
+            ]
+        );
+        assert!(answers["day10a"].is_empty());
+    }
+
+    #[test]
+    fn parses_multi_line_literals() {
+        let text = "[day10a]\n\"in.txt\" = '''\n#..#\n#\\.#\n'''\n\"x\" = '''one'''\n";
+        let answers = parse(text).unwrap();
+        assert_eq!(answers["day10a"][0].1, "#..#\n#\\.#\n");
+        assert_eq!(answers["day10a"][1].1, "one");
+    }
+
+    #[test]
+    fn reports_line_numbers() {
+        assert_eq!(parse("\n\"a\" = \"b\"").unwrap_err(), "line 2: Key outside a section");
+        assert_eq!(
+            parse("[x]\n\"a\" = \"b").unwrap_err(),
+            "line 2: Unterminated string"
+        );
+        assert!(parse("[x]\n\"a\" = '''\nabc\n").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+// Timing and memory measurement of the solvers, along with reading
+// and writing reports so runs can be compared over time.
+//
+// Reports are CSV, one line per solver, with times in nanoseconds and
+// memory in bytes:
+//
+//   solver,runs,min_ns,median_ns,max_ns,peak_bytes
+
+use std::time::{Duration, Instant};
+
+use aoc_common::Result;
+use memory;
+use Solver;
+
+const HEADER: &str = "solver,runs,min_ns,median_ns,max_ns,peak_bytes";
+
+#[derive(Debug, PartialEq)]
+pub struct Measurement {
+    pub name: String,
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    // Most memory allocated by the solver at any one time. Zero unless
+    // memory::CountingAlloc is the global allocator.
+    pub peak_bytes: usize,
+}
+
+fn median(sorted: &[Duration]) -> Duration {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2
+    } else {
+        sorted[mid]
+    }
+}
+
+// Run the solver the given number of times. The input is read up
+// front, so only the solving is measured. Stops at the first error.
+pub fn measure(solver: &Solver, input: &str, runs: usize) -> Result<Measurement> {
+    assert!(runs > 0, "Need at least one run");
+    let mut times = Vec::new();
+    let mut peak_bytes = 0;
+    for _ in 0..runs {
+        let base = memory::reset_peak();
+        let start = Instant::now();
+        let answer = (solver.solve)(input);
+        times.push(start.elapsed());
+        peak_bytes = peak_bytes.max(memory::peak() - base);
+        drop(answer?);
+    }
+    times.sort();
+
+    Ok(Measurement {
+        name: solver.name(),
+        runs,
+        min: times[0],
+        median: median(&times),
+        max: times[times.len() - 1],
+        peak_bytes,
+    })
+}
+
+pub fn to_csv(measurements: &[Measurement]) -> String {
+    let mut res = String::new();
+    res.push_str(HEADER);
+    res.push('\n');
+    for m in measurements.iter() {
+        res.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            m.name,
+            m.runs,
+            m.min.as_nanos(),
+            m.median.as_nanos(),
+            m.max.as_nanos(),
+            m.peak_bytes
+        ));
+    }
+    res
+}
+
+pub fn from_csv(text: &str) -> std::result::Result<Vec<Measurement>, String> {
+    let mut lines = text.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim() == HEADER => {}
+        _ => return Err(format!("line 1: Expected header \"{}\"", HEADER)),
+    }
+
+    let mut res = Vec::new();
+    for (line_no, line) in lines {
+        let err = |msg: &str| format!("line {}: {}", line_no + 1, msg);
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+        if fields.len() != 6 {
+            return Err(err("Expected 6 fields"));
+        }
+        let num = |s: &str| s.parse::<u64>().map_err(|_| err(&format!("Bad number: {}", s)));
+        res.push(Measurement {
+            name: fields[0].to_string(),
+            runs: num(fields[1])? as usize,
+            min: Duration::from_nanos(num(fields[2])?),
+            median: Duration::from_nanos(num(fields[3])?),
+            max: Duration::from_nanos(num(fields[4])?),
+            peak_bytes: num(fields[5])? as usize,
+        });
+    }
+    Ok(res)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs_f64();
+    if secs >= 1.0 {
+        format!("{:.2}s", secs)
+    } else if secs >= 1e-3 {
+        format!("{:.2}ms", secs * 1e3)
+    } else {
+        format!("{:.2}us", secs * 1e6)
+    }
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1024.0 * 1024.0 {
+        format!("{:.1}MiB", bytes / (1024.0 * 1024.0))
+    } else if bytes >= 1024.0 {
+        format!("{:.1}KiB", bytes / 1024.0)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
+// Percentage change from old to new, for comparing against a baseline.
+pub fn change(old: f64, new: f64) -> String {
+    if old == 0.0 {
+        if new == 0.0 { "0%".to_string() } else { "new".to_string() }
+    } else {
+        format!("{:+.1}%", (new - old) / old * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_odd_and_even() {
+        let ms = |v: &[u64]| v.iter().map(|x| Duration::from_millis(*x)).collect::<Vec<_>>();
+        assert_eq!(median(&ms(&[1, 2, 9])), Duration::from_millis(2));
+        assert_eq!(median(&ms(&[1, 2, 4, 9])), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let m = vec![Measurement {
+            name: "day01a".to_string(),
+            runs: 3,
+            min: Duration::from_nanos(100),
+            median: Duration::from_nanos(150),
+            max: Duration::from_nanos(300),
+            peak_bytes: 4096,
+        }];
+        let csv = to_csv(&m);
+        assert_eq!(csv, format!("{}\nday01a,3,100,150,300,4096\n", HEADER));
+        assert_eq!(from_csv(&csv).unwrap(), m);
+    }
+
+    #[test]
+    fn csv_errors() {
+        assert!(from_csv("nonsense").is_err());
+        let bad = format!("{}\nday01a,3,100,x,300,4096\n", HEADER);
+        assert_eq!(from_csv(&bad).unwrap_err(), "line 2: Bad number: x");
+    }
+}
@@ -0,0 +1,390 @@
+// Registry of all the solutions, so that they can be driven from a
+// single binary rather than a shell script full of "cargo run"s.
+
+extern crate aoc_common;
+extern crate day01a;
+extern crate day01b;
+extern crate day02a;
+extern crate day02b;
+extern crate day03a;
+extern crate day03b;
+extern crate day04a;
+extern crate day04b;
+extern crate day05a;
+extern crate day05b;
+extern crate day06a;
+extern crate day06b;
+extern crate day07a;
+extern crate day07b;
+extern crate day08a;
+extern crate day08b;
+extern crate day09;
+extern crate day10;
+extern crate day11a;
+extern crate day11b;
+extern crate day12a;
+extern crate day12b;
+extern crate day13a;
+extern crate day13b;
+extern crate day14a;
+extern crate day14b;
+extern crate day15a;
+extern crate day15b;
+extern crate day16a;
+extern crate day16b;
+extern crate day17;
+extern crate day18a;
+extern crate day18b;
+extern crate day19;
+extern crate day20a;
+extern crate day20b;
+extern crate day21;
+extern crate day22a;
+extern crate day22b;
+extern crate day23a;
+extern crate day23b;
+extern crate day24a;
+extern crate day24b;
+extern crate day25a;
+
+pub mod answers;
+pub mod bench;
+pub mod memory;
+
+use aoc_common::Result;
+use std::path::PathBuf;
+
+pub struct Solver {
+    pub day: u32,
+    pub part: char,
+    // Default input, relative to the workspace root.
+    pub input: &'static str,
+    pub solve: fn(&str) -> Result<String>,
+}
+
+impl Solver {
+    pub fn name(&self) -> String {
+        format!("day{:02}{}", self.day, self.part)
+    }
+
+    pub fn default_input(&self) -> PathBuf {
+        workspace_root().join(self.input)
+    }
+}
+
+// Where the dayNN directories live.
+pub fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("No workspace root")
+        .to_path_buf()
+}
+
+// Some crates solve both parts in one go, in which case each part
+// picks its own bit of the answer.
+pub const SOLVERS: &[Solver] = &[
+    Solver {
+        day: 1,
+        part: 'a',
+        input: "day01a/input/input.txt",
+        solve: |s| day01a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 1,
+        part: 'b',
+        input: "day01a/input/input.txt",
+        solve: |s| day01b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 2,
+        part: 'a',
+        input: "day02a/input/input.txt",
+        solve: |s| day02a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 2,
+        part: 'b',
+        input: "day02a/input/input.txt",
+        solve: |s| day02b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 3,
+        part: 'a',
+        input: "day03a/input/input.txt",
+        solve: |s| day03a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 3,
+        part: 'b',
+        input: "day03a/input/input.txt",
+        solve: |s| day03b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 4,
+        part: 'a',
+        input: "day04a/input/input.txt",
+        solve: |s| day04a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 4,
+        part: 'b',
+        input: "day04a/input/input.txt",
+        solve: |s| day04b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 5,
+        part: 'a',
+        input: "day05a/input/input.txt",
+        solve: |s| day05a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 5,
+        part: 'b',
+        input: "day05a/input/input.txt",
+        solve: |s| day05b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 6,
+        part: 'a',
+        input: "day06a/input/input.txt",
+        solve: |s| day06a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 6,
+        part: 'b',
+        input: "day06a/input/input.txt",
+        solve: |s| day06b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 7,
+        part: 'a',
+        input: "day07a/input/input.txt",
+        solve: |s| day07a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 7,
+        part: 'b',
+        input: "day07a/input/input.txt",
+        solve: |s| day07b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 8,
+        part: 'a',
+        input: "day08a/input/input.txt",
+        solve: |s| day08a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 8,
+        part: 'b',
+        input: "day08a/input/input.txt",
+        solve: |s| day08b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 9,
+        part: 'a',
+        input: "day09/input/input.txt",
+        solve: |s| day09::solve(s).map(|a| a.high_score.to_string()),
+    },
+    Solver {
+        day: 9,
+        part: 'b',
+        input: "day09/input/input.txt",
+        solve: |s| day09::solve(s).map(|a| a.high_score_x100.to_string()),
+    },
+    Solver {
+        day: 10,
+        part: 'a',
+        input: "day10/input/input.txt",
+        // The drawing, if its letters can't be read.
+        solve: |s| {
+            day10::solve(s).map(|a| match a.text {
+                Ok(text) => text,
+                Err(e) => format!("{}\n({})", a.message, e),
+            })
+        },
+    },
+    Solver {
+        day: 10,
+        part: 'b',
+        input: "day10/input/input.txt",
+        solve: |s| day10::solve(s).map(|a| a.time.to_string()),
+    },
+    Solver {
+        day: 11,
+        part: 'a',
+        input: "day11a/input/input.txt",
+        solve: |s| day11a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 11,
+        part: 'b',
+        input: "day11a/input/input.txt",
+        solve: |s| day11b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 12,
+        part: 'a',
+        input: "day12a/input/input.txt",
+        solve: |s| day12a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 12,
+        part: 'b',
+        input: "day12a/input/input.txt",
+        solve: |s| day12b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 13,
+        part: 'a',
+        input: "day13a/input/input.txt",
+        solve: |s| day13a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 13,
+        part: 'b',
+        input: "day13a/input/input.txt",
+        solve: |s| day13b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 14,
+        part: 'a',
+        input: "day14a/input/input.txt",
+        solve: |s| day14a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 14,
+        part: 'b',
+        input: "day14a/input/input.txt",
+        solve: |s| day14b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 15,
+        part: 'a',
+        input: "day15a/input/input.txt",
+        solve: |s| day15a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 15,
+        part: 'b',
+        input: "day15a/input/input.txt",
+        solve: |s| day15b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 16,
+        part: 'a',
+        input: "day16a/input/input.txt",
+        solve: |s| day16a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 16,
+        part: 'b',
+        input: "day16a/input/input.txt",
+        solve: |s| day16b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 17,
+        part: 'a',
+        input: "day17/input/input.txt",
+        solve: |s| day17::solve(s).map(|a| a.water.to_string()),
+    },
+    Solver {
+        day: 17,
+        part: 'b',
+        input: "day17/input/input.txt",
+        solve: |s| day17::solve(s).map(|a| a.settled_water.to_string()),
+    },
+    Solver {
+        day: 18,
+        part: 'a',
+        input: "day18a/input/input.txt",
+        solve: |s| day18a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 18,
+        part: 'b',
+        input: "day18a/input/input.txt",
+        solve: |s| day18b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 19,
+        part: 'a',
+        input: "day19/input/input.txt",
+        solve: |s| day19::solve(s).map(|a| a.first.to_string()),
+    },
+    Solver {
+        day: 19,
+        part: 'b',
+        input: "day19/input/input.txt",
+        solve: |s| day19::solve(s).map(|a| a.second.to_string()),
+    },
+    Solver {
+        day: 20,
+        part: 'a',
+        input: "day20a/input/input.txt",
+        solve: |s| day20a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 20,
+        part: 'b',
+        input: "day20a/input/input.txt",
+        solve: |s| day20b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 21,
+        part: 'a',
+        input: "day21/input/input.txt",
+        solve: |s| day21::solve(s).map(|a| a.first.to_string()),
+    },
+    Solver {
+        day: 21,
+        part: 'b',
+        input: "day21/input/input.txt",
+        solve: |s| day21::solve(s).map(|a| a.last.to_string()),
+    },
+    Solver {
+        day: 22,
+        part: 'a',
+        input: "day22a/input/input.txt",
+        solve: |s| day22a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 22,
+        part: 'b',
+        input: "day22a/input/input.txt",
+        solve: |s| day22b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 23,
+        part: 'a',
+        input: "day23a/input/input.txt",
+        solve: |s| day23a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 23,
+        part: 'b',
+        input: "day23a/input/input.txt",
+        solve: |s| day23b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 24,
+        part: 'a',
+        input: "day24a/input/input_expanded.txt",
+        solve: |s| day24a::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 24,
+        part: 'b',
+        input: "day24a/input/input_expanded.txt",
+        solve: |s| day24b::solve(s).map(|a| a.to_string()),
+    },
+    Solver {
+        day: 25,
+        part: 'a',
+        input: "day25a/input/input.txt",
+        solve: |s| day25a::solve(s).map(|a| a.to_string()),
+    },
+];
+
+pub fn find(day: u32, part: char) -> Option<&'static Solver> {
+    SOLVERS.iter().find(|s| s.day == day && s.part == part)
+}
@@ -0,0 +1,207 @@
+extern crate aoc;
+extern crate aoc_common;
+
+use aoc::bench::{self, Measurement};
+use aoc::memory::CountingAlloc;
+use aoc::{Solver, SOLVERS};
+use aoc_common::Error;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+// Track allocations so that benchmarks can report peak memory.
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+const USAGE: &str = "Usage:
+    aoc run <day> <part> [--input <path>]
+    aoc all
+    aoc list
+    aoc bench [<day> [<part>]] [--runs <n>] [--save <csv>] [--baseline <csv>]";
+
+const DEFAULT_RUNS: usize = 5;
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("{}\n\n{}", msg, USAGE);
+    process::exit(2);
+}
+
+fn parse_day(s: &str) -> u32 {
+    match s.trim_start_matches("day").parse() {
+        Ok(day) => day,
+        Err(_) => usage_error(&format!("Bad day: {}", s)),
+    }
+}
+
+fn parse_part(s: &str) -> char {
+    match s {
+        "a" | "1" => 'a',
+        "b" | "2" => 'b',
+        _ => usage_error(&format!("Bad part: {}", s)),
+    }
+}
+
+fn read_input(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path.display(), e))
+}
+
+// Problems with the input are reported against the file it came from.
+fn input_error(path: &Path, e: Error) -> String {
+    e.with_file(&path.display().to_string()).to_string()
+}
+
+// Run a solver, on the given input file if there is one, or its
+// default input otherwise.
+fn run(solver: &Solver, input: Option<PathBuf>) -> Result<(), String> {
+    let path = input.unwrap_or_else(|| solver.default_input());
+    let text = read_input(&path)?;
+    let answer = (solver.solve)(&text).map_err(|e| input_error(&path, e))?;
+    println!("{}", answer);
+    Ok(())
+}
+
+fn cmd_run(args: &[String]) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut input = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--input" {
+            match iter.next() {
+                Some(path) => input = Some(PathBuf::from(path)),
+                None => usage_error("--input needs a path"),
+            }
+        } else {
+            positional.push(arg);
+        }
+    }
+    if positional.len() != 2 {
+        usage_error("Need a day and a part");
+    }
+
+    let (day, part) = (parse_day(positional[0]), parse_part(positional[1]));
+    match aoc::find(day, part) {
+        Some(solver) => run(solver, input),
+        None => Err(format!("No solver for day {} part {}", day, part)),
+    }
+}
+
+fn cmd_all() -> Result<(), String> {
+    for solver in SOLVERS.iter() {
+        println!("=== {}", solver.name());
+        run(solver, None)?;
+    }
+    Ok(())
+}
+
+fn cmd_list() -> Result<(), String> {
+    for solver in SOLVERS.iter() {
+        println!("{} {}", solver.name(), solver.input);
+    }
+    Ok(())
+}
+
+fn print_measurement(m: &Measurement, baseline: Option<&Measurement>) {
+    print!(
+        "{:<8} {:>4} {:>10} {:>10} {:>10} {:>10}",
+        m.name,
+        m.runs,
+        bench::format_duration(m.min),
+        bench::format_duration(m.median),
+        bench::format_duration(m.max),
+        bench::format_bytes(m.peak_bytes)
+    );
+    if let Some(b) = baseline {
+        print!(
+            " {:>10} {:>10}",
+            bench::change(b.median.as_secs_f64(), m.median.as_secs_f64()),
+            bench::change(b.peak_bytes as f64, m.peak_bytes as f64)
+        );
+    }
+    println!();
+}
+
+fn cmd_bench(args: &[String]) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut runs = DEFAULT_RUNS;
+    let mut save = None;
+    let mut baseline = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |what: &str| match iter.next() {
+            Some(v) => v.clone(),
+            None => usage_error(&format!("{} needs {}", arg, what)),
+        };
+        match arg.as_str() {
+            "--runs" => match value("a count").parse() {
+                Ok(n) if n > 0 => runs = n,
+                _ => usage_error("--runs needs a positive count"),
+            },
+            "--save" => save = Some(PathBuf::from(value("a path"))),
+            "--baseline" => baseline = Some(PathBuf::from(value("a path"))),
+            _ => positional.push(arg),
+        }
+    }
+
+    let solvers = match positional.len() {
+        0 => SOLVERS.iter().collect::<Vec<_>>(),
+        1 => {
+            let day = parse_day(positional[0]);
+            SOLVERS.iter().filter(|s| s.day == day).collect()
+        }
+        2 => {
+            let (day, part) = (parse_day(positional[0]), parse_part(positional[1]));
+            SOLVERS.iter().filter(|s| s.day == day && s.part == part).collect()
+        }
+        _ => usage_error("Too many arguments"),
+    };
+    if solvers.is_empty() {
+        return Err("No matching solvers".to_string());
+    }
+
+    let baseline = match baseline {
+        Some(path) => {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+            bench::from_csv(&text).map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        None => Vec::new(),
+    };
+
+    print!("{:<8} {:>4} {:>10} {:>10} {:>10} {:>10}", "solver", "runs", "min", "median", "max", "peak mem");
+    if !baseline.is_empty() {
+        print!(" {:>10} {:>10}", "median chg", "mem chg");
+    }
+    println!();
+
+    let mut measurements = Vec::new();
+    for solver in solvers.iter() {
+        let path = solver.default_input();
+        let text = read_input(&path)?;
+        let m = bench::measure(solver, &text, runs).map_err(|e| input_error(&path, e))?;
+        print_measurement(&m, baseline.iter().find(|b| b.name == m.name));
+        measurements.push(m);
+    }
+
+    if let Some(path) = save {
+        fs::write(&path, bench::to_csv(&measurements))
+            .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let res = match args.first().map(String::as_str) {
+        Some("run") => cmd_run(&args[1..]),
+        Some("all") => cmd_all(),
+        Some("list") => cmd_list(),
+        Some("bench") => cmd_bench(&args[1..]),
+        Some(cmd) => usage_error(&format!("Unknown command: {}", cmd)),
+        None => usage_error("No command given"),
+    };
+    if let Err(msg) = res {
+        eprintln!("{}", msg);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,68 @@
+// Regression tests: run every solver over the inputs listed in
+// answers.toml, and check they still give the recorded answers.
+
+extern crate aoc;
+
+use std::fs;
+
+fn check(name: &str) {
+    let answers = aoc::answers::load().unwrap();
+    let solver = aoc::SOLVERS
+        .iter()
+        .find(|s| s.name() == name)
+        .unwrap_or_else(|| panic!("No solver {}", name));
+    let cases = answers
+        .get(name)
+        .unwrap_or_else(|| panic!("No answers for {}", name));
+    assert!(!cases.is_empty(), "No answers for {}", name);
+
+    let mut failures = Vec::new();
+    for (path, expected) in cases.iter() {
+        let input = fs::read_to_string(aoc::workspace_root().join(path))
+            .unwrap_or_else(|e| panic!("Couldn't read {}: {}", path, e));
+        let actual = match (solver.solve)(&input) {
+            Ok(actual) => actual,
+            Err(e) => {
+                failures.push(format!("{}: {}", name, e.with_file(path)));
+                continue;
+            }
+        };
+        if actual.trim_end() != expected.trim_end() {
+            failures.push(format!(
+                "{} on {}:\nexpected: {}\n  actual: {}",
+                name, path, expected, actual
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+macro_rules! answer_tests {
+    ($($name:ident),*) => {
+        $(
+            #[test]
+            fn $name() {
+                check(stringify!($name));
+            }
+        )*
+
+        #[test]
+        fn every_solver_is_tested() {
+            let tested = [$(stringify!($name)),*];
+            for solver in aoc::SOLVERS.iter() {
+                assert!(tested.contains(&solver.name().as_str()), "{} untested", solver.name());
+            }
+            for name in aoc::answers::load().unwrap().keys() {
+                assert!(tested.contains(&name.as_str()), "[{}] has no solver", name);
+            }
+        }
+    };
+}
+
+answer_tests!(
+    day01a, day01b, day02a, day02b, day03a, day03b, day04a, day04b, day05a, day05b,
+    day06a, day06b, day07a, day07b, day08a, day08b, day09a, day09b, day10a, day10b,
+    day11a, day11b, day12a, day12b, day13a, day13b, day14a, day14b, day15a, day15b,
+    day16a, day16b, day17a, day17b, day18a, day18b, day19a, day19b, day20a, day20b, day21a,
+    day21b, day22a, day22b, day23a, day23b, day24a, day24b, day25a
+);
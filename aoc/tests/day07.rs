@@ -0,0 +1,168 @@
+// Day 7's ordering and scheduling, configured as in the puzzle's
+// example, and on steps with longer names.
+
+extern crate aoc;
+extern crate day07b;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+fn example_deps() -> day07b::Deps {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../day07a/input/example.txt");
+    day07b::parse(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn example_config() -> day07b::Config {
+    day07b::Config {
+        workers: 2,
+        base: 0,
+        ..day07b::Config::default()
+    }
+}
+
+fn example() -> day07b::Schedule {
+    day07b::schedule(&example_deps(), &example_config()).unwrap()
+}
+
+#[test]
+fn schedules_example() {
+    let schedule = example();
+    assert_eq!(schedule.finish(), 15);
+    assert_eq!(
+        schedule.to_csv(),
+        "step,worker,start,end\nC,1,0,3\nA,1,3,4\nF,2,3,9\nB,1,4,6\nD,1,6,10\nE,1,10,15\n"
+    );
+}
+
+#[test]
+fn draws_gantt_chart() {
+    assert_eq!(
+        example().gantt(1),
+        "  1 CCCABBDDDDEEEEE\n  2 ...FFFFFF......\n    0             15\n"
+    );
+    assert_eq!(example().gantt(5), "  1 CBE\n  2 .F.\n    0 15\n");
+}
+
+#[test]
+fn exports_json() {
+    let json = example().to_json();
+    assert!(json.starts_with("[\n  {\"step\": \"C\", \"worker\": 1, \"start\": 0, \"end\": 3},\n"));
+    assert!(json.ends_with("{\"step\": \"E\", \"worker\": 1, \"start\": 10, \"end\": 15}\n]\n"));
+}
+
+#[test]
+fn finds_critical_path() {
+    // With unlimited workers, F doesn't have to wait for A.
+    let path = day07b::critical_path(&example_deps(), &example_config()).unwrap();
+    assert_eq!(path.steps, vec!["C", "F", "E"]);
+    assert_eq!(path.length, 14);
+    assert_eq!(path.to_string(), "C -> F -> E (14)");
+}
+
+const BUILD: &str = "Step fetch must be finished before step compile can begin.\n\
+                     Step fetch must be finished before step docs can begin.\n\
+                     Step compile must be finished before step test,all can begin.\n";
+
+fn build_config() -> day07b::Config {
+    let table = day07b::parse_durations("fetch 5\ncompile 30\ndocs 12\ntest,all 20\n").unwrap();
+    day07b::Config {
+        workers: 2,
+        base: 0,
+        ..day07b::Config::default()
+    }.with_table(table)
+}
+
+#[test]
+fn schedules_named_steps() {
+    let deps = day07b::parse(BUILD).unwrap();
+    let schedule = day07b::schedule(&deps, &build_config()).unwrap();
+    assert_eq!(schedule.finish(), 55);
+    assert_eq!(
+        schedule.to_csv(),
+        "step,worker,start,end\nfetch,1,0,5\ncompile,1,5,35\ndocs,2,5,17\n\"test,all\",1,35,55\n"
+    );
+    assert_eq!(schedule.gantt(5), "  1 fcompiltest\n  2 .doc.......\n    0         55\n");
+    let path = day07b::critical_path(&deps, &build_config()).unwrap();
+    assert_eq!(path.to_string(), "fetch -> compile -> test,all (55)");
+}
+
+#[test]
+fn needs_durations_for_named_steps() {
+    let deps = day07b::parse(BUILD).unwrap();
+    let err = day07b::schedule(&deps, &day07b::Config::default()).unwrap_err();
+    assert_eq!(err.to_string(), "No duration for step compile");
+}
+
+#[test]
+fn rejects_negative_durations() {
+    let err = day07b::parse_durations("fetch 5\ncompile -30\n").unwrap_err();
+    assert_eq!(err.with_file("durations.txt").to_string(), "durations.txt:2:9: expected a duration of 0 or more, found \"-30\"");
+
+    let deps = example_deps();
+    let config = day07b::Config {
+        base: -1,
+        ..example_config()
+    };
+    assert!(day07b::schedule(&deps, &config).is_err());
+    let config = example_config().with_table(vec![("C".to_string(), -5)].into_iter().collect());
+    let err = day07b::critical_path(&deps, &config).unwrap_err();
+    assert_eq!(err.to_string(), "Negative duration for step C");
+}
+
+#[test]
+fn rejects_durations_too_long_to_add_up() {
+    let deps = day07b::parse("Step A must be finished before step B can begin.\n").unwrap();
+    let table = day07b::parse_durations("A 2147483647\n").unwrap();
+    let config = day07b::Config::default().with_table(table);
+    let err = day07b::schedule(&deps, &config).unwrap_err();
+    assert_eq!(err.to_string(), "Step A takes too long to keep track of");
+
+    let table = day07b::parse_durations("A 2147483000\nB 2147483000\n").unwrap();
+    let config = day07b::Config {
+        base: 0,
+        ..day07b::Config::default()
+    }.with_table(table);
+    let err = day07b::schedule(&deps, &config).unwrap_err();
+    assert_eq!(err.to_string(), "Step B would finish too late to keep track of");
+    let err = day07b::critical_path(&deps, &config).unwrap_err();
+    assert_eq!(err.to_string(), "Step B would finish too late to keep track of");
+}
+
+#[test]
+fn finds_critical_path_of_long_chain() {
+    // Each step depends on the one before.
+    let n = 1_000_000;
+    let name = |i: i32| format!("s{:07}", i);
+    let mut deps = (0..n).map(|i| (name(i), HashSet::new())).collect::<day07b::Deps>();
+    for i in 1..n {
+        deps.get_mut(&name(i)).unwrap().insert(name(i - 1));
+    }
+    let config = day07b::Config {
+        duration: Box::new(|_| Some(1)),
+        ..example_config()
+    };
+    let path = day07b::critical_path(&deps, &config).unwrap();
+    assert_eq!(path.length, n);
+    assert_eq!(path.steps.len(), n as usize);
+    assert_eq!(path.steps[0], "s0000000");
+}
+
+#[test]
+fn copes_with_lots_of_workers() {
+    let config = day07b::Config {
+        workers: usize::MAX,
+        ..example_config()
+    };
+    let schedule = day07b::schedule(&example_deps(), &config).unwrap();
+    // With a worker for every step, it's the critical path.
+    assert_eq!(schedule.finish(), 14);
+    assert_eq!(schedule.workers, usize::MAX);
+    assert_eq!(schedule.gantt(1).lines().count(), 7);
+}
+
+#[test]
+fn orders_named_steps() {
+    let solver = aoc::find(7, 'a').unwrap();
+    assert_eq!((solver.solve)(BUILD).unwrap(), "fetch compile docs test,all");
+}
@@ -0,0 +1,92 @@
+// Day 8's license tree, read from and written back to its numbers.
+
+extern crate day08a;
+
+use std::fs;
+use std::path::Path;
+
+const EXAMPLE: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
+
+#[test]
+fn evaluates_example() {
+    let root = day08a::parse(EXAMPLE).unwrap();
+    assert_eq!(root.children.len(), 2);
+    assert_eq!(root.metadata, vec![1, 1, 2]);
+    assert_eq!(root.metadata_sum(), 138);
+    assert_eq!(root.value(), 66);
+}
+
+#[test]
+fn clones_tree() {
+    let root = day08a::parse(EXAMPLE).unwrap();
+    assert_eq!(root.clone(), root);
+    assert_ne!(day08a::parse("1 1 0 1 5 2").unwrap(), day08a::parse("1 1 0 1 5 3").unwrap());
+    assert_ne!(day08a::parse("1 1 0 1 5 2").unwrap(), day08a::parse("1 1 0 1 6 2").unwrap());
+}
+
+#[test]
+fn prints_tree() {
+    assert_eq!(
+        format!("{:?}", day08a::parse("2 1 0 1 5 0 0 2").unwrap()),
+        "Node { children: [Node { children: [], metadata: [5] }, Node { children: [], metadata: [] }], metadata: [2] }"
+    );
+}
+
+#[test]
+fn serializes_back() {
+    assert_eq!(day08a::parse(EXAMPLE).unwrap().serialize(), EXAMPLE);
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../day08a/input/input.txt");
+    let input = fs::read_to_string(path).unwrap();
+    assert_eq!(day08a::parse(&input).unwrap().serialize(), input.trim());
+}
+
+#[test]
+fn dumps_tree() {
+    assert_eq!(
+        day08a::parse(EXAMPLE).unwrap().dump(),
+        "- [1 1 2]\n  - [10 11 12]\n  - [2]\n    - [99]\n"
+    );
+}
+
+#[test]
+fn reports_truncated_stream() {
+    let err = |input| day08a::parse(input).unwrap_err().to_string();
+    assert_eq!(err("2 3 0 3 10 11 12 1 1 0 1 99 2 1"), "line 1, column 32: expected a metadata entry, found end of line");
+    assert_eq!(err("2 3 0 3 10 11 12"), "line 1, column 17: expected the number of children, found end of line");
+    assert_eq!(err("1 1 0"), "line 1, column 6: expected the number of metadata entries, found end of line");
+}
+
+// A tree nested "depth" deep. Each node but the last has a leaf worth
+// 3 and then the next node as children, and metadata "2 3", which
+// picks out the next node for its value and points past the end for
+// the other entry. The last node is a leaf worth 5.
+fn deep_tree(depth: usize) -> String {
+    let mut input = "2 2 0 1 3 ".repeat(depth - 1);
+    input += "0 1 5";
+    input += &" 2 3".repeat(depth - 1);
+    input
+}
+
+#[test]
+fn handles_deep_trees() {
+    let depth = 2_000_000;
+    let input = deep_tree(depth);
+    let root = day08a::parse(&input).unwrap();
+    assert_eq!(root.metadata_sum(), (depth - 1) * 8 + 5);
+    assert_eq!(root.value(), 5);
+    assert_eq!(root.serialize(), input);
+    let copy = root.clone();
+    assert_eq!(copy, root);
+    assert_eq!(format!("{:?}", copy).len(), format!("{:?}", root).len());
+    drop(root);
+    assert_eq!(copy.serialize(), input);
+    assert_ne!(copy, day08a::parse(&input.replace("0 1 5", "0 1 6")).unwrap());
+}
+
+#[test]
+fn dumps_deep_trees() {
+    // The indentation makes this quadratic, so not quite so deep.
+    let dump = day08a::parse(&deep_tree(1000)).unwrap().dump();
+    assert_eq!(dump.lines().count(), 1999);
+    assert_eq!(dump.lines().last().unwrap(), format!("{}- [5]", "  ".repeat(999)));
+}
@@ -0,0 +1,150 @@
+// Day 9's closed-form marble game, checked against playing it out.
+
+extern crate day09;
+
+use day09::Rules;
+use std::collections::VecDeque;
+
+// The circle, with the current marble at the back.
+fn play_out_with(players: usize, last_marble: usize, rules: &Rules) -> Vec<usize> {
+    let mut circle = VecDeque::new();
+    circle.push_back(0);
+    let mut scores = vec![0; players];
+    for marble in 1..=last_marble {
+        let len = circle.len();
+        if marble % rules.multiple == 0 {
+            circle.rotate_right(rules.back % len);
+            scores[(marble - 1) % players] += marble + circle.pop_back().unwrap();
+            circle.rotate_left(1 % circle.len());
+        } else {
+            circle.rotate_left(rules.insert_after % len);
+            circle.push_back(marble);
+        }
+    }
+    scores
+}
+
+fn play_out(players: usize, last_marble: usize) -> Vec<usize> {
+    play_out_with(players, last_marble, &Rules::default())
+}
+
+fn high_score(scores: Vec<usize>) -> usize {
+    scores.into_iter().max().unwrap()
+}
+
+#[test]
+fn matches_playing_out() {
+    let games = [(1, 100), (9, 25), (10, 1618), (13, 7999), (430, 71588), (7, 22), (7, 23), (5, 0)];
+    for &(players, last_marble) in games.iter() {
+        let expected = play_out(players, last_marble);
+        assert_eq!(day09::play(players, last_marble), expected, "{} {}", players, last_marble);
+    }
+    // Long enough to go past the cached entries.
+    assert_eq!(day09::play(41, 2_000_000), play_out(41, 2_000_000));
+}
+
+#[test]
+fn reports_every_player() {
+    let answer = day09::solve("9 players; last marble is worth 25 points").unwrap();
+    assert_eq!(answer.scores, vec![0, 0, 0, 0, 32, 0, 0, 0, 0]);
+    assert_eq!(answer.high_score, 32);
+}
+
+#[test]
+fn replays_example() {
+    let mut game = day09::Game::new(9, 25).with_snapshots();
+    let turns = game.by_ref().collect::<Vec<_>>();
+    assert_eq!(turns.len(), 25);
+    assert_eq!(
+        turns[22],
+        day09::Turn {
+            turn: 23,
+            player: 4,
+            marble: 23,
+            score: 32,
+            circle: Some(vec![0, 16, 8, 17, 4, 18, 19, 2, 20, 10, 21, 5, 22, 11, 1, 12, 6, 13, 3, 14, 7, 15]),
+        }
+    );
+    assert_eq!(
+        turns[24].circle,
+        Some(vec![0, 16, 8, 17, 4, 18, 19, 2, 24, 20, 25, 10, 21, 5, 22, 11, 1, 12, 6, 13, 3, 14, 7, 15])
+    );
+    assert_eq!(game.current(), 25);
+    assert_eq!(game.render(2), "24 20 (25) 10 21");
+    // Bigger than the circle, so each marble is shown once.
+    assert_eq!(game.render(100).split(' ').count(), 24);
+}
+
+#[test]
+fn replay_scores_match() {
+    let mut scores = vec![0; 13];
+    for turn in day09::Game::new(13, 7999) {
+        assert_eq!(turn.circle, None);
+        scores[turn.player] += turn.score;
+    }
+    assert_eq!(scores, day09::play(13, 7999));
+}
+
+#[test]
+fn default_rules_match_examples() {
+    let examples = [(9, 25, 32), (10, 1618, 8317), (13, 7999, 146373), (17, 1104, 2764), (21, 6111, 54718), (30, 5807, 37305)];
+    let rules = Rules::default();
+    for &(players, last_marble, expected) in examples.iter() {
+        assert_eq!(high_score(day09::play_with(players, last_marble, &rules)), expected);
+        // Played out rather than in closed form.
+        let mut scores = vec![0; players];
+        for turn in day09::Game::new(players, last_marble).with_rules(rules) {
+            scores[turn.player] += turn.score;
+        }
+        assert_eq!(high_score(scores), expected);
+    }
+}
+
+#[test]
+fn variants_match_playing_out() {
+    let variants = [(2, 0, 0), (2, 1, 1), (5, 3, 0), (23, 7, 2), (19, 9, 1), (30, 40, 5)];
+    for &(multiple, back, insert_after) in variants.iter() {
+        let rules = Rules {
+            multiple,
+            back,
+            insert_after,
+        };
+        for &(players, last_marble) in [(1, 100), (9, 250), (13, 7999)].iter() {
+            assert_eq!(
+                day09::play_with(players, last_marble, &rules),
+                play_out_with(players, last_marble, &rules),
+                "{:?} {} {}",
+                rules,
+                players,
+                last_marble
+            );
+        }
+    }
+}
+
+#[test]
+fn variant_circle_and_score() {
+    // Every 4th marble scores, taking the marble 2 back, and the rest
+    // go right after the current one.
+    let rules = Rules {
+        multiple: 4,
+        back: 2,
+        insert_after: 0,
+    };
+    let mut game = day09::Game::new(2, 6).with_rules(rules).with_snapshots();
+    let turns = game.by_ref().collect::<Vec<_>>();
+    assert_eq!(turns[3].score, 4 + 1);
+    assert_eq!(turns[3].circle, Some(vec![0, 2, 3]));
+    assert_eq!(turns[5].circle, Some(vec![0, 2, 5, 6, 3]));
+    assert_eq!(game.circle_len(), 5);
+    assert_eq!(day09::solve_with("2 players; last marble is worth 6 points", &rules).unwrap().scores, vec![0, 5]);
+}
+
+#[test]
+fn rejects_scoring_every_marble() {
+    let rules = Rules {
+        multiple: 1,
+        ..Rules::default()
+    };
+    assert!(day09::solve_with("9 players; last marble is worth 25 points", &rules).is_err());
+}
@@ -0,0 +1,72 @@
+// Day 10's search for when the message appears.
+
+extern crate aoc;
+extern crate day10;
+
+use std::fs;
+use std::path::Path;
+
+fn read(path: &str) -> String {
+    fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join(path)).unwrap()
+}
+
+// The input, as it would have been "seconds" earlier.
+fn rewind(input: &str, seconds: i64) -> String {
+    let mut out = String::new();
+    for line in input.lines() {
+        let nums = line
+            .split(|c: char| c != '-' && !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<i64>().unwrap())
+            .collect::<Vec<_>>();
+        let (px, py, vx, vy) = (nums[0], nums[1], nums[2], nums[3]);
+        out += &format!(
+            "position=<{}, {}> velocity=<{}, {}>\n",
+            px - seconds * vx,
+            py - seconds * vy,
+            vx,
+            vy
+        );
+    }
+    out
+}
+
+#[test]
+fn finds_message_from_far_away() {
+    let input = read("../day10/input/input.txt");
+    let answer = day10::solve(&input).unwrap();
+    let seconds = 100_000_000;
+    let rewound = day10::solve(&rewind(&input, seconds)).unwrap();
+    assert_eq!(rewound.time, answer.time + seconds);
+    assert_eq!(rewound.message, answer.message);
+}
+
+#[test]
+fn message_already_showing() {
+    let input = read("../day10/input/example.txt");
+    let answer = day10::solve(&rewind(&input, -3)).unwrap();
+    assert_eq!(answer.time, 0);
+    assert_eq!(answer.message, day10::solve(&input).unwrap().message);
+}
+
+#[test]
+fn rejects_points_that_never_meet() {
+    // Closest at the start, and too far apart to draw.
+    let input = "position=<0, 0> velocity=<-1, 0>\nposition=<100000, 100000> velocity=<1, 0>\n";
+    assert!(day10::solve(input).is_err());
+}
+
+#[test]
+fn shows_drawing_it_cant_read() {
+    // A 2 by 10 block, which isn't a letter.
+    let mut input = String::new();
+    for y in 0..10 {
+        for x in 0..2 {
+            input += &format!("position=<{}, {}> velocity=<0, 0>\n", x, y);
+        }
+    }
+    let solver = aoc::find(10, 'a').unwrap();
+    let answer = (solver.solve)(&input).unwrap();
+    assert!(answer.starts_with(&"##\n".repeat(10)), "{}", answer);
+    assert!(answer.contains("(Unrecognised letters in the message"), "{}", answer);
+}
@@ -0,0 +1,35 @@
+// Day 16 on made-up inputs, where the answers are known from how they
+// were made.
+
+extern crate aoc;
+extern crate elfcode;
+
+use elfcode::samples::{self, Rng};
+
+fn solve(part: char, input: &str) -> String {
+    let solver = aoc::find(16, part).unwrap();
+    (solver.solve)(input).unwrap_or_else(|e| panic!("{}: {}", solver.name(), e))
+}
+
+#[test]
+fn solves_generated_inputs() {
+    for seed in 0..10 {
+        let mut rng = Rng::new(seed);
+        let mapping = samples::random_mapping(&mut rng);
+        let ambiguous = rng.below(500);
+        let gen = samples::generate(&mut rng, &mapping, 500, ambiguous, 200).unwrap();
+        let input = gen.to_string();
+        assert_eq!(solve('a', &input), ambiguous.to_string(), "seed {}", seed);
+        assert_eq!(solve('b', &input), gen.result().to_string(), "seed {}", seed);
+    }
+}
+
+#[test]
+fn reports_too_few_samples() {
+    let mut rng = Rng::new(1);
+    let mapping = samples::random_mapping(&mut rng);
+    let gen = samples::generate(&mut rng, &mapping, 5, 5, 1).unwrap();
+    let solver = aoc::find(16, 'b').unwrap();
+    let err = (solver.solve)(&gen.to_string()).unwrap_err().to_string();
+    assert!(err.starts_with("The samples don't pin down opcodes"), "{}", err);
+}
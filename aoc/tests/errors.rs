@@ -0,0 +1,54 @@
+// Bad input should give an error saying where the problem is, rather
+// than a panic.
+
+extern crate aoc;
+
+fn error(day: u32, part: char, input: &str) -> String {
+    let solver = aoc::find(day, part).unwrap();
+    match (solver.solve)(input) {
+        Ok(answer) => panic!("{} accepted bad input, giving {}", solver.name(), answer),
+        Err(e) => e.with_file("input.txt").to_string(),
+    }
+}
+
+#[test]
+fn reports_position_of_bad_token() {
+    assert_eq!(
+        error(3, 'a', "#1 @ 1,3: 4x4\n#2 @ 3,1: 4y4\n"),
+        "input.txt:2:12: expected \"x\", found \"y4\""
+    );
+    assert_eq!(
+        error(19, 'a', "#ip 0\nseti 5 0 1\nfoo 1 2 3\n"),
+        "input.txt:3:1: expected an opcode, found \"foo\""
+    );
+    assert_eq!(
+        error(17, 'a', "x=495, y=2..7\ny=7, y=495..501\n"),
+        "input.txt:2:6: expected \"x\", found \"y\""
+    );
+}
+
+#[test]
+fn reports_bad_grid_characters() {
+    assert_eq!(
+        error(13, 'a', "/->-\\\n|  |\n\\-*-/\n"),
+        "input.txt:3:3: expected track or a cart, found '*'"
+    );
+}
+
+#[test]
+fn reports_missing_input() {
+    assert_eq!(
+        error(22, 'a', "depth: 510\n"),
+        "input.txt:2:1: expected \"target:\", found end of input"
+    );
+    assert_eq!(error(9, 'a', ""), "input.txt:1:1: expected a game description, found end of input");
+}
+
+#[test]
+fn reports_input_without_a_solution() {
+    let cycle = "Step C must be finished before step A can begin.\n\
+                 Step A must be finished before step C can begin.\n";
+    let msg = "input.txt: The steps depend on each other in a cycle: A -> C -> A";
+    assert_eq!(error(7, 'a', cycle), msg);
+    assert_eq!(error(7, 'b', cycle), msg);
+}
@@ -0,0 +1,11 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::Result;
+
+pub type Answer = i32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let parts = scan::parse_lines(input, |s| s.number::<i32>())?;
+    Ok(parts.iter().sum())
+}
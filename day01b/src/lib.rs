@@ -0,0 +1,25 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{Error, Result};
+use std::collections::HashSet;
+
+pub type Answer = i32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let parts = scan::parse_lines(input, |s| s.number::<i32>())?;
+    if parts.is_empty() {
+        return Err(Error::invalid("No frequency changes"));
+    }
+
+    let mut seen = HashSet::new();
+    let mut running_sum = 0;
+    loop {
+        for part in parts.iter() {
+            running_sum += part;
+            if !seen.insert(running_sum) {
+                return Ok(running_sum);
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+extern crate aoc_common;
+
+use aoc_common::{Error, Result};
+
+fn distance(str1: &str, str2: &str) -> i32 {
+    // Assumes strings same length.
+    let mut iter1 = str1.trim().chars();
+    let mut iter2 = str2.trim().chars();
+    let mut dist = 0;
+    loop {
+        match (iter1.next(), iter2.next()) {
+            (Some(c1), Some(c2)) => if c1 != c2 {
+                dist += 1;
+            },
+            _ => return dist,
+        }
+    }
+}
+
+fn shared(str1: &str, str2: &str) -> String {
+    // Assumes strings same length.
+    str1.trim()
+        .chars()
+        .zip(str2.trim().chars())
+        .filter(|(c1, c2)| c1 == c2)
+        .map(|(c1, _)| c1)
+        .collect()
+}
+
+pub type Answer = String;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let lines: Vec<&str> = input.lines().collect();
+
+    // Number of lines is short, let's brute-force.
+    for line1 in lines.iter() {
+        for line2 in lines.iter() {
+            if distance(line1, line2) == 1 {
+                return Ok(shared(line1, line2));
+            }
+        }
+    }
+    Err(Error::invalid("No pair of IDs differing by one character"))
+}
@@ -0,0 +1,97 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result, Scanner};
+use std::collections::BTreeMap;
+
+// Representation of rectangle with lower bound included, upper bound
+// excluded.
+#[derive(Debug)]
+struct Rect {
+    x_min: i32,
+    x_max: i32,
+    y_min: i32,
+    y_max: i32,
+}
+
+// Lines look like "#123 @ 3,2: 5x4".
+fn read_rect(s: &mut Scanner) -> ParseResult<Rect> {
+    // We don't need the id.
+    s.literal("#")?;
+    s.number::<i32>()?;
+    s.literal("@")?;
+    let x = s.number::<i32>()?;
+    s.literal(",")?;
+    let y = s.number::<i32>()?;
+    s.literal(":")?;
+    let w = s.number::<i32>()?;
+    s.literal("x")?;
+    let h = s.number::<i32>()?;
+    // Convert from origin/size to min/max coords.
+    Ok(Rect {
+        x_min: x,
+        x_max: x + w,
+        y_min: y,
+        y_max: y + h,
+    })
+}
+
+pub type Answer = i32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let rects = scan::parse_lines(input, read_rect)?;
+
+    // And now we scan convert, starting by making a list of changes
+    // that happen as we scan the y direction.
+    let mut y_deltas = BTreeMap::new();
+    for rect in rects.iter() {
+        {
+            let y_min_entry = y_deltas.entry(rect.y_min).or_insert_with(Vec::new);
+            (*y_min_entry).push((rect.x_min, 1));
+            (*y_min_entry).push((rect.x_max, -1));
+        }
+        {
+            let y_max_entry = y_deltas.entry(rect.y_max).or_insert_with(Vec::new);
+            (*y_max_entry).push((rect.x_min, -1));
+            (*y_max_entry).push((rect.x_max, 1));
+        }
+    }
+
+    // Now, let's step through the ordered y changes, updating and
+    // processing the x extents and using that to calculate accumulated
+    // area.
+    let mut area = 0;
+    let mut last_extent = 0;
+    let mut last_y = 0;
+    let mut x_deltas = BTreeMap::new();
+    for (y, y_delta) in y_deltas.iter() {
+        // Start by accumulating area since the last y_delta, update y.
+        area += last_extent * (y - last_y);
+        last_y = *y;
+        // Now update our current x_deltas.
+        for (x, x_delta) in y_delta.iter() {
+            let updated_value = {
+                let x_entry = x_deltas.entry(*x).or_insert(0);
+                *x_entry += x_delta;
+                *x_entry
+            };
+            if updated_value == 0 {
+                x_deltas.remove(x);
+            }
+        }
+        // And calculate our current accumulating extent.
+        last_extent = 0;
+        let mut last_x = 0;
+        let mut overlap_count = 0;
+        for (x, x_delta) in x_deltas.iter() {
+            // Accumulate extent since last x_delta, update x.
+            if overlap_count > 1 {
+                last_extent += *x - last_x;
+            }
+            last_x = *x;
+            // Update current overlap.
+            overlap_count += x_delta;
+        }
+    }
+    Ok(area)
+}
@@ -0,0 +1,154 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{Error, ParseResult, Result, Scanner};
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+// Representation of rectangle with lower bound included, upper bound
+// excluded.
+#[derive(Debug)]
+struct Rect {
+    id: i32,
+    x_min: i32,
+    x_max: i32,
+    y_min: i32,
+    y_max: i32,
+}
+
+// Lines look like "#123 @ 3,2: 5x4".
+fn read_rect(s: &mut Scanner) -> ParseResult<Rect> {
+    s.literal("#")?;
+    let id = s.number::<i32>()?;
+    s.literal("@")?;
+    let x = s.number::<i32>()?;
+    s.literal(",")?;
+    let y = s.number::<i32>()?;
+    s.literal(":")?;
+    let w = s.number::<i32>()?;
+    s.literal("x")?;
+    let h = s.number::<i32>()?;
+    // Convert from origin/size to min/max coords.
+    Ok(Rect {
+        id,
+        x_min: x,
+        x_max: x + w,
+        y_min: y,
+        y_max: y + h,
+    })
+}
+
+// Representation of edges at a point. Stored left and right edge ids.
+#[derive(Debug)]
+struct Edge {
+    end_ids: HashSet<i32>,
+    start_ids: HashSet<i32>,
+}
+
+impl Edge {
+    fn new() -> Edge {
+        Edge { end_ids: HashSet::new(), start_ids: HashSet::new() }
+    }
+
+    fn add_left(&mut self, id: i32) {
+        self.start_ids.insert(id);
+    }
+
+    fn add_right(&mut self, id: i32) {
+        self.end_ids.insert(id);
+    }
+
+    fn remove_left(&mut self, id: i32) {
+        self.start_ids.remove(&id);
+    }
+
+    fn remove_right(&mut self, id: i32) {
+        self.end_ids.remove(&id);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.end_ids.is_empty() && self.start_ids.is_empty()
+    }
+}
+
+pub type Answer = i32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let rects = scan::parse_lines(input, read_rect)?;
+
+    #[derive(PartialEq)]
+    enum LR {
+        Left,
+        Right
+    }
+    #[derive(PartialEq)]
+    enum TB {
+        Top,
+        Bottom
+    }
+
+    // And now we scan convert, starting by making a list of changes
+    // that happen as we scan the y direction.
+    let mut y_deltas = BTreeMap::new();
+    for rect in rects.iter() {
+        {
+            let y_min_entry = y_deltas.entry(rect.y_min).or_insert_with(Vec::new);
+            (*y_min_entry).push((rect.x_min, rect.id, LR::Left, TB::Top));
+            (*y_min_entry).push((rect.x_max, rect.id, LR::Right, TB::Top));
+        }
+        {
+            let y_max_entry = y_deltas.entry(rect.y_max).or_insert_with(Vec::new);
+            (*y_max_entry).push((rect.x_min, rect.id, LR::Left, TB::Bottom));
+            (*y_max_entry).push((rect.x_max, rect.id, LR::Right, TB::Bottom));
+        }
+    }
+
+    // Build the set of rectangle ids.
+    let mut rect_ids = rects.iter().map(|r| r.id).collect::<HashSet<_>>();
+
+    // Now, let's step through the ordered y changes, updating and
+    // processing the x extents to find overlaps.
+    let mut x_deltas = BTreeMap::new();
+    for y_delta in y_deltas.values() {
+        // Update our current x_deltas.
+        for (x, x_id, lr, tb) in y_delta.iter() {
+            let to_remove = {
+                let x_entry = x_deltas.entry(*x).or_insert(Edge::new());
+                match (lr, tb) {
+                    (LR::Left, TB::Top) => (*x_entry).add_left(*x_id),
+                    (LR::Right, TB::Top) => (*x_entry).add_right(*x_id),
+                    (LR::Left, TB::Bottom) => (*x_entry).remove_left(*x_id),
+                    (LR::Right, TB::Bottom) => (*x_entry).remove_right(*x_id),
+                }
+                (*x_entry).is_empty()
+            };
+            if to_remove {
+                x_deltas.remove(x);
+            }
+        }
+        // And find the current overlaps.
+        let mut inside = HashSet::new();
+        for x_delta in x_deltas.values() {
+            // Update current overlaps.
+            for end in x_delta.end_ids.iter() {
+                inside.remove(end);
+            }
+            for start in x_delta.start_ids.iter() {
+                inside.insert(start);
+            }
+            // And chuck out rectangles that overlap.
+            if inside.len() > 1 {
+                for id in inside.iter() {
+                    rect_ids.remove(id);
+                }
+            }
+        }
+    }
+    if rect_ids.len() != 1 {
+        return Err(Error::invalid(format!(
+            "Expected exactly one non-overlapping claim, found {}",
+            rect_ids.len()
+        )));
+    }
+    Ok(*rect_ids.iter().next().unwrap())
+}
@@ -0,0 +1,113 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{Error, ParseResult, Result, Scanner};
+use std::collections::HashMap;
+
+#[derive(Debug)]
+enum Entry {
+    Shift(i32),
+    Sleep(i32),
+    Wake(i32),
+}
+
+// Year, month, day, hour, minute, so that they sort by time.
+type Timestamp = (i32, i32, i32, i32, i32);
+
+// Lines look like "[1518-11-01 00:05] falls asleep".
+fn parse_line(s: &mut Scanner) -> ParseResult<(Timestamp, Entry)> {
+    s.literal("[")?;
+    let year = s.number()?;
+    s.literal("-")?;
+    let month = s.number()?;
+    s.literal("-")?;
+    let day = s.number()?;
+    let hour = s.number()?;
+    s.literal(":")?;
+    let minute = s.number()?;
+    s.literal("]")?;
+    let entry = match s.keyword(&["Guard", "falls", "wakes"])? {
+        "Guard" => {
+            s.literal("#")?;
+            let num = s.number()?;
+            s.literal("begins shift")?;
+            Entry::Shift(num)
+        }
+        "falls" => {
+            s.literal("asleep")?;
+            Entry::Sleep(minute)
+        }
+        _ => {
+            s.literal("up")?;
+            Entry::Wake(minute)
+        }
+    };
+    Ok(((year, month, day, hour, minute), entry))
+}
+
+fn analyse_guard(sleep_times: &[(i32, i32)]) -> (i32, i32) {
+    // And as there are only 60 minutes in an hour, let's just create
+    // a small array...
+    let mut minutes = vec![0; 60];
+
+    for (s, e) in sleep_times.iter() {
+        for minute in *s..*e {
+            minutes[minute as usize] += 1;
+        }
+    }
+
+    let sleep_iter = (0..).zip(minutes.iter());
+    // Return sleepiest minute, and the sleep count.
+    sleep_iter.fold(
+        (-1, -1),
+        |(om, oc), (m, c)| if *c > oc { (m, *c) } else { (om, oc) },
+    )
+}
+
+pub type Answer = i32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut lines = scan::parse_lines(input, parse_line)?;
+
+    // Put the entries in time order.
+    lines.sort_by_key(|(time, _)| *time);
+
+    let entries = lines.into_iter().map(|(_, entry)| entry).collect::<Vec<_>>();
+
+    // For this puzzle, we don't need to track days when there was no
+    // sleep, so the code is nice and simple...
+    let mut sleep_map = HashMap::new();
+    let mut guard = -1;
+    let mut last_sleep = 0;
+
+    for line in entries.iter() {
+        // Mostly assume entries are well-structured and don't bother
+        // sanity-checking. I'm lazy.
+        match line {
+            Entry::Shift(g) => guard = *g,
+            _ if guard == -1 => return Err(Error::invalid("Sleep before any guard's shift")),
+            Entry::Sleep(t) => last_sleep = *t,
+            Entry::Wake(t) => {
+                let entry = sleep_map.entry(guard).or_insert_with(Vec::new);
+                (*entry).push((last_sleep, *t));
+            }
+        }
+    }
+
+    // Build an iterator of guard/sleeps.
+    let sleeps = sleep_map
+        .iter()
+        .map(|(g, v)| (g, analyse_guard(v)));
+
+
+    // And find the sleepiest:
+    let (sleepiest_guard, (sleepiest_minute, _)) = sleeps.fold(
+        (-1, (-1, -1)),
+        |(og, (om, oc)), (g, (m, c))| if c > oc { (*g, (m, c)) } else { (og, (om, oc)) },
+    );
+
+    if sleepiest_guard == -1 {
+        return Err(Error::invalid("No guard ever sleeps"));
+    }
+    Ok(sleepiest_guard * sleepiest_minute)
+}
@@ -0,0 +1,88 @@
+
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result};
+
+pub type Answer = i32;
+
+// The polymer should be a single line of nothing but unit letters.
+fn check_polymer(input: &str) -> ParseResult<()> {
+    scan::parse_single(input, "a polymer", |s| {
+        s.skip_whitespace();
+        while let Some(c) = s.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            if !c.is_ascii_alphabetic() {
+                return Err(s.error("a unit letter"));
+            }
+            s.next_char();
+        }
+        Ok(())
+    })
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    check_polymer(input)?;
+
+    // Linked list would be the best representation. For simplicity,
+    // let's fake it with arrays of "pointers". Fortran-style at its
+    // best. We'll put in ^sentinels$ at either end of the array.
+
+
+    let mut data = Vec::new();
+    data.push('^');
+    for c in input.trim().chars() {
+        data.push(c);
+    }
+    data.push('$');
+
+    // Initialise like this:
+    //
+    // prev: -1 0  1  2  3
+    // data: ^  X  Y  Z  $
+    // next: 1  2  3  4  -1
+    //
+    // Yes, I'm a bad person for using '-1' instead of Option.
+
+    let n = data.len() as i32;
+    let mut next = (1..n + 1).collect::<Vec<_>>();
+    next[(n - 1) as usize] = -1;
+    let mut prev = (-1..n - 1).collect::<Vec<_>>();
+
+    // And now traverse the "list", looking for pairs to elide. We
+    // always elide he current-pointed-to item and the next item.
+
+    let mut p: i32 = 0;
+    while next[p as usize] != -1 {
+        let next_p = next[p as usize];
+        let c = data[p as usize];
+        let next_c = data[next_p as usize];
+
+        if c.eq_ignore_ascii_case(&next_c) && c != next_c {
+            // Candidate for reaction!
+            let forward = next[next_p as usize];
+            let backward = prev[p as usize];
+            next[backward as usize] = forward;
+            prev[forward as usize] = backward;
+            p = backward;
+        } else {
+            // Nothing, move on.
+            p = next_p;
+        }
+    }
+
+    // Get the length. Could be merged into previous loop, but this is
+    // simpler.
+
+    let mut l = 0;
+    p = 0;
+    while p != -1 {
+        l += 1;
+        p = next[p as usize];
+    }
+
+    // Remove sentinels from count.
+    Ok(l - 2)
+}
@@ -0,0 +1,62 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result, Scanner};
+
+
+fn pair_parse(s: &mut Scanner) -> ParseResult<(i32, i32)> {
+    let x = s.number()?;
+    s.literal(",")?;
+    let y = s.number()?;
+    Ok((x, y))
+}
+
+fn sum_distance(points: &[(i32, i32)], x: i32, y: i32) -> i32 {
+    points
+        .iter()
+        .map(|(px, py)| (x - px).abs() + (y - py).abs())
+        .sum()
+}
+
+pub type Answer = i32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let coords = scan::parse_lines(input, pair_parse)?;
+    if coords.is_empty() {
+        return Err(scan::unexpected_end(input, "a coordinate").into());
+    }
+
+    let xs: Vec<_> = coords.iter().map(|(x, _)| *x).collect();
+    let ys: Vec<_> = coords.iter().map(|(_, y)| *y).collect();
+
+    let min_x = *xs.iter().min().unwrap();
+    let max_x = *xs.iter().max().unwrap();
+    let min_y = *ys.iter().min().unwrap();
+    let max_y = *ys.iter().max().unwrap();
+
+    let mut near_count = 0;
+
+    // Let's brute-force this because it's a lot easier than the
+    // alternatives. We'll keep count of nearest points for all
+    // coordinates.
+
+    // We'll just slap an extra border on because it doesn't make the
+    // cost prohibitive, and it's easier than special-casing the
+    // calculations for outside the edge.
+    let extra = (10000 / coords.len() as i32) + 1;
+
+    let min_x = min_x - extra;
+    let max_x = max_x + extra;
+    let min_y = min_y - extra;
+    let max_y = max_y + extra;
+
+    for x in min_x..max_x + 1 {
+        for y in min_y..max_y + 1 {
+            if sum_distance(&coords, x, y) < 10000 {
+                near_count += 1;
+            }
+        }
+    }
+
+    Ok(near_count)
+}
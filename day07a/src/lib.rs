@@ -0,0 +1,100 @@
+extern crate aoc_common;
+
+use aoc_common::graph;
+use aoc_common::scan;
+use aoc_common::{Error, ParseResult, Result, Scanner};
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+// Steps are named by anything without spaces in it. The puzzle only
+// uses single letters.
+fn step(s: &mut Scanner) -> ParseResult<String> {
+    s.skip_whitespace();
+    let mut name = String::new();
+    while let Some(c) = s.peek().filter(|c| !c.is_whitespace()) {
+        name.push(c);
+        s.next_char();
+    }
+    if name.is_empty() {
+        return Err(s.error("a step name"));
+    }
+    Ok(name)
+}
+
+// Pull out the graph nodes, from lines like "Step C must be finished
+// before step A can begin."
+fn pair_parse(s: &mut Scanner) -> ParseResult<(String, String)> {
+    s.literal("Step")?;
+    let before = step(s)?;
+    s.literal("must be finished before step")?;
+    let after = step(s)?;
+    s.literal("can begin.")?;
+    Ok((before, after))
+}
+
+// Report the steps that can never start.
+fn check_cycles(deps: &BTreeMap<String, HashSet<String>>) -> Result<()> {
+    match graph::find_cycle(deps) {
+        None => Ok(()),
+        Some(mut steps) => {
+            steps.push(steps[0].clone());
+            Err(Error::invalid(format!(
+                "The steps depend on each other in a cycle: {}",
+                steps.join(" -> ")
+            )))
+        }
+    }
+}
+
+// Ties go to the lexicographically first, through the map's ordering.
+fn find_first_no_dep(deps: &BTreeMap<String, HashSet<String>>) -> Option<String> {
+    for (node, node_deps) in deps.iter() {
+        if node_deps.is_empty() {
+            return Some(node.clone());
+        }
+    }
+    None
+}
+
+fn remove_node(deps: &mut BTreeMap<String, HashSet<String>>, to_remove: &str) {
+    deps.remove(to_remove);
+    for (_node, node_deps) in deps.iter_mut() {
+        node_deps.remove(to_remove);
+    }
+}
+
+// The steps in the order they're done. The puzzle wants single letters
+// run together, but longer names need spaces between them.
+pub struct Answer(pub Vec<String>);
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let sep = if self.0.iter().all(|s| s.chars().count() == 1) { "" } else { " " };
+        write!(f, "{}", self.0.join(sep))
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let edges = scan::parse_lines(input, pair_parse)?;
+
+    // Build set of deps for each node.
+    let mut deps = BTreeMap::new();
+    for (before, after) in edges.into_iter() {
+        deps.entry(before.clone()).or_insert(HashSet::new());
+        let after_entry = deps.entry(after).or_insert(HashSet::new());
+        (*after_entry).insert(before);
+    }
+    check_cycles(&deps)?;
+
+    // Now, let's do a brute-force and dumb "find earliest node with
+    // dep, output it, remove, repeat" algorithm. This is ok since the
+    // data size is small. It's easy to implement.
+    let mut order = Vec::new();
+    while !deps.is_empty() {
+        let node = find_first_no_dep(&deps)
+            .ok_or_else(|| Error::invalid("The steps depend on each other in a cycle"))?;
+        remove_node(&mut deps, &node);
+        order.push(node);
+    }
+    Ok(Answer(order))
+}
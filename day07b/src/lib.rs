@@ -0,0 +1,422 @@
+extern crate aoc_common;
+
+use aoc_common::graph;
+use aoc_common::scan;
+use aoc_common::{Error, ParseResult, Result, Scanner};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+
+// The set of steps each step depends on.
+pub type Deps = BTreeMap<String, HashSet<String>>;
+
+// Steps are named by anything without spaces in it. The puzzle only
+// uses single letters.
+fn step(s: &mut Scanner) -> ParseResult<String> {
+    s.skip_whitespace();
+    let mut name = String::new();
+    while let Some(c) = s.peek().filter(|c| !c.is_whitespace()) {
+        name.push(c);
+        s.next_char();
+    }
+    if name.is_empty() {
+        return Err(s.error("a step name"));
+    }
+    Ok(name)
+}
+
+// Pull out the graph nodes, from lines like "Step C must be finished
+// before step A can begin."
+fn pair_parse(s: &mut Scanner) -> ParseResult<(String, String)> {
+    s.literal("Step")?;
+    let before = step(s)?;
+    s.literal("must be finished before step")?;
+    let after = step(s)?;
+    s.literal("can begin.")?;
+    Ok((before, after))
+}
+
+// Report the steps that can never start.
+fn check_cycles(deps: &Deps) -> Result<()> {
+    match graph::find_cycle(deps) {
+        None => Ok(()),
+        Some(mut steps) => {
+            steps.push(steps[0].clone());
+            Err(Error::invalid(format!(
+                "The steps depend on each other in a cycle: {}",
+                steps.join(" -> ")
+            )))
+        }
+    }
+}
+
+// Ties between steps that are ready at the same time go to the
+// lexicographically first, through the map's ordering.
+fn find_first_no_dep(deps: &Deps) -> Option<String> {
+    for (node, node_deps) in deps.iter() {
+        if node_deps.is_empty() {
+            return Some(node.clone());
+        }
+    }
+    None
+}
+
+// A step's own duration, if known.
+pub type DurationFn = Box<dyn Fn(&str) -> Option<i32>>;
+
+// How many workers there are, and how long each step takes them.
+pub struct Config {
+    pub workers: usize,
+    // Added to every step's own duration.
+    pub base: i32,
+    pub duration: DurationFn,
+}
+
+// A step's own duration in the puzzle: 1 for A, up to 26 for Z.
+pub fn letter_duration(step: &str) -> Option<i32> {
+    let mut chars = step.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_uppercase() => Some((c as i32) - ('A' as i32) + 1),
+        _ => None,
+    }
+}
+
+impl Config {
+    // Take durations from the table, falling back to the puzzle's for
+    // steps that aren't in it.
+    pub fn with_table(self, table: BTreeMap<String, i32>) -> Config {
+        Config {
+            duration: Box::new(move |step| table.get(step).cloned().or_else(|| letter_duration(step))),
+            ..self
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            workers: 5,
+            base: 60,
+            duration: Box::new(letter_duration),
+        }
+    }
+}
+
+// One step's run on a worker, from start (inclusive) to end
+// (exclusive). Workers are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    pub step: String,
+    pub worker: usize,
+    pub start: i32,
+    pub end: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    pub workers: usize,
+    // In the order they were started.
+    pub tasks: Vec<Task>,
+}
+
+// Quoted if need be.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// "n / d" rounded up, for "n" of 0 or more, without overflowing.
+fn div_ceil(n: i32, d: i32) -> i32 {
+    n / d + (n % d != 0) as i32
+}
+
+impl Schedule {
+    // When the last step finishes.
+    pub fn finish(&self) -> i32 {
+        self.tasks.iter().map(|t| t.end).max().unwrap_or(0)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("step,worker,start,end\n");
+        for t in self.tasks.iter() {
+            out += &format!("{},{},{},{}\n", csv_field(&t.step), t.worker, t.start, t.end);
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let tasks = self
+            .tasks
+            .iter()
+            .map(|t| {
+                format!(
+                    "  {{\"step\": {}, \"worker\": {}, \"start\": {}, \"end\": {}}}",
+                    json_string(&t.step),
+                    t.worker,
+                    t.start,
+                    t.end
+                )
+            }).collect::<Vec<_>>();
+        format!("[\n{}\n]\n", tasks.join(",\n"))
+    }
+
+    // A row per worker, with a column for every "scale" seconds,
+    // showing the step running at the start of it, or '.' if idle. A
+    // step's name is repeated along its bar, so single letters fill
+    // it and longer names can be read from its start. Workers past the
+    // number of steps can never have had any, and are left out.
+    pub fn gantt(&self, scale: i32) -> String {
+        let finish = self.finish();
+        let columns = div_ceil(finish, scale);
+        let mut out = String::new();
+        for worker in 1..=self.workers.min(self.tasks.len()) {
+            out += &format!("{:>3} ", worker);
+            for col in 0..columns {
+                let time = col * scale;
+                let running = self
+                    .tasks
+                    .iter()
+                    .find(|t| t.worker == worker && t.start <= time && time < t.end);
+                out.push(match running {
+                    None => '.',
+                    Some(t) => {
+                        // Columns since the bar's first.
+                        let offset = col - div_ceil(t.start, scale);
+                        t.step.chars().cycle().nth(offset as usize).unwrap()
+                    }
+                });
+            }
+            out.push('\n');
+        }
+        out += &format!("{:>3} 0{:>width$}\n", "", finish, width = columns as usize);
+        out
+    }
+}
+
+// When a step started at "start" finishes.
+fn finish_time(step: &str, start: i32, duration: i32) -> Result<i32> {
+    start
+        .checked_add(duration)
+        .ok_or_else(|| Error::invalid(format!("Step {} would finish too late to keep track of", step)))
+}
+
+fn schedule_work(
+    events: &mut BTreeMap<i32, Vec<(String, usize)>>,
+    deps: &mut Deps,
+    curr_time: i32,
+    free_workers: &mut BTreeSet<usize>,
+    durations: &BTreeMap<String, i32>,
+    tasks: &mut Vec<Task>,
+) -> Result<()> {
+    loop {
+        // Hand out work to the lowest-numbered worker first.
+        let worker = match free_workers.iter().next() {
+            None => return Ok(()),
+            Some(worker) => *worker,
+        };
+        match find_first_no_dep(deps) {
+            None => return Ok(()),
+            Some(step) => {
+                let end = finish_time(&step, curr_time, durations[&step])?;
+                deps.remove(&step);
+                events.entry(end).or_default().push((step.clone(), worker));
+                tasks.push(Task {
+                    step,
+                    worker,
+                    start: curr_time,
+                    end,
+                });
+                free_workers.remove(&worker);
+            }
+        }
+    }
+}
+
+fn complete_work(
+    events: &mut BTreeMap<i32, Vec<(String, usize)>>,
+    deps: &mut Deps,
+    curr_time: &mut i32,
+    free_workers: &mut BTreeSet<usize>,
+) -> Result<()> {
+    let to_remove = match events.iter().next() {
+        // Nothing to do, but not complete.
+        None => return Err(Error::invalid("The steps depend on each other in a cycle")),
+        Some((t, nodes)) => {
+            *curr_time = *t;
+            for (step, worker) in nodes.iter() {
+                // Unblock the work...
+                for (_node, node_deps) in deps.iter_mut() {
+                    node_deps.remove(step);
+                }
+                // and free the worker.
+                free_workers.insert(*worker);
+            }
+            *t
+        }
+    };
+    events.remove(&to_remove);
+    Ok(())
+}
+
+pub fn parse(input: &str) -> Result<Deps> {
+    let edges = scan::parse_lines(input, pair_parse)?;
+
+    let mut deps = BTreeMap::new();
+    for (before, after) in edges.into_iter() {
+        deps.entry(before.clone()).or_insert(HashSet::new());
+        let after_entry = deps.entry(after).or_insert(HashSet::new());
+        (*after_entry).insert(before);
+    }
+    Ok(deps)
+}
+
+// A table of durations, from lines like "compile 30".
+pub fn parse_durations(input: &str) -> Result<BTreeMap<String, i32>> {
+    let entries = scan::parse_lines(input, |s| {
+        let name = step(s)?;
+        s.skip_whitespace();
+        let column = s.column();
+        let duration: i32 = s.number()?;
+        if duration < 0 {
+            return Err(s.error_at(column, "a duration of 0 or more", &duration.to_string()));
+        }
+        Ok((name, duration))
+    })?;
+    Ok(entries.into_iter().collect())
+}
+
+// How long each step takes, including the base duration. Negative
+// durations would have steps finish before they start.
+fn durations(deps: &Deps, config: &Config) -> Result<BTreeMap<String, i32>> {
+    if config.base < 0 {
+        return Err(Error::invalid("The base duration can't be negative"));
+    }
+    deps.keys()
+        .map(|step| match (config.duration)(step) {
+            Some(duration) if duration < 0 => Err(Error::invalid(format!("Negative duration for step {}", step))),
+            Some(duration) => match config.base.checked_add(duration) {
+                Some(duration) => Ok((step.clone(), duration)),
+                None => Err(Error::invalid(format!("Step {} takes too long to keep track of", step))),
+            },
+            None => Err(Error::invalid(format!("No duration for step {}", step))),
+        }).collect()
+}
+
+pub fn schedule(deps: &Deps, config: &Config) -> Result<Schedule> {
+    if config.workers == 0 {
+        return Err(Error::invalid("There are no workers to do the steps"));
+    }
+    check_cycles(deps)?;
+    let durations = durations(deps, config)?;
+    let mut deps = deps.clone();
+
+    // Use a fairly brute-force approach. At each time step, we
+    // keep assigning work until we're out of workers or work.
+    // Then we move time forward, complete work, etc. Work goes to the
+    // lowest-numbered workers, so any more workers than steps would
+    // never get any.
+    let mut free_workers = (1..=config.workers.min(deps.len())).collect::<BTreeSet<_>>();
+    let mut curr_time = 0;
+    let mut events = BTreeMap::new();
+    let mut tasks = Vec::new();
+
+    schedule_work(&mut events, &mut deps, curr_time, &mut free_workers, &durations, &mut tasks)?;
+    while !deps.is_empty() {
+        complete_work(&mut events, &mut deps, &mut curr_time, &mut free_workers)?;
+        schedule_work(&mut events, &mut deps, curr_time, &mut free_workers, &durations, &mut tasks)?;
+    }
+
+    Ok(Schedule {
+        workers: config.workers,
+        tasks,
+    })
+}
+
+// The longest chain of steps, which is how long the work takes with
+// as many workers as there are steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriticalPath {
+    pub length: i32,
+    // In the order they're done.
+    pub steps: Vec<String>,
+}
+
+impl std::fmt::Display for CriticalPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} ({})", self.steps.join(" -> "), self.length)
+    }
+}
+
+// When each step finishes at the earliest, with unlimited workers.
+// Steps wait on the stack for what they depend on, rather than
+// recursing, as chains of steps can be far deeper than the call stack.
+fn earliest_finishes(deps: &Deps, durations: &BTreeMap<String, i32>) -> Result<BTreeMap<String, i32>> {
+    let mut finishes = BTreeMap::new();
+    for step in deps.keys() {
+        let mut stack = vec![step];
+        while let Some(&step) = stack.last() {
+            if finishes.contains_key(step) {
+                stack.pop();
+                continue;
+            }
+            let waiting = deps[step].iter().filter(|dep| !finishes.contains_key(*dep)).collect::<Vec<_>>();
+            if !waiting.is_empty() {
+                stack.extend(waiting);
+                continue;
+            }
+            let start = deps[step].iter().map(|dep| finishes[dep]).max().unwrap_or(0);
+            finishes.insert(step.clone(), finish_time(step, start, durations[step])?);
+            stack.pop();
+        }
+    }
+    Ok(finishes)
+}
+
+// The worker count in the config doesn't matter here. Ties go to the
+// lexicographically first step, as elsewhere.
+pub fn critical_path(deps: &Deps, config: &Config) -> Result<CriticalPath> {
+    check_cycles(deps)?;
+    let durations = durations(deps, config)?;
+    let finishes = earliest_finishes(deps, &durations)?;
+
+    // Work back from the last step to finish, through whichever
+    // dependency held it up.
+    let latest = |steps: &mut dyn Iterator<Item = &String>| steps.max_by_key(|s| (finishes[*s], Reverse(*s))).cloned();
+    let mut steps = Vec::new();
+    let mut next = latest(&mut deps.keys());
+    while let Some(step) = next {
+        next = latest(&mut deps[&step].iter());
+        steps.push(step);
+    }
+    steps.reverse();
+
+    Ok(CriticalPath {
+        length: steps.last().map_or(0, |step| finishes[step]),
+        steps,
+    })
+}
+
+pub type Answer = i32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let deps = parse(input)?;
+    Ok(schedule(&deps, &Config::default())?.finish())
+}
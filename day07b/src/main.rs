@@ -1,106 +1,82 @@
-use std::collections::BTreeMap;
-use std::collections::HashSet;
+extern crate day07b;
+
+use day07b::Config;
+use std::env;
+use std::fs;
 use std::io;
-use std::io::BufRead;
+use std::io::Read;
+use std::process;
 
-// Pull out the graph nodes.
-fn pair_parse(str: &str) -> (char, char) {
-    let snipped = str
-        .trim()
-        .replace("Step ", "")
-        .replace(" can begin.", "")
-        .chars()
-        .collect::<Vec<_>>();
-    (snipped[0], snipped[snipped.len() - 1])
-}
+const USAGE: &str = "Usage: day07b [--workers <n>] [--base <n>] [--durations <file>]
+              [--csv | --json | --gantt [--scale <n>] | --critical] < input
 
-fn find_first_no_dep(deps: &BTreeMap<char, HashSet<char>>) -> Option<char> {
-    for (node, node_deps) in deps.iter() {
-        if node_deps.is_empty() {
-            return Some(*node);
-        }
-    }
-    None
-}
+Durations are given as lines like \"compile 30\". Steps not in the
+file take the puzzle's duration, if they're a single letter.";
 
-fn schedule_work(
-    events: &mut BTreeMap<i32, Vec<char>>,
-    deps: &mut BTreeMap<char, HashSet<char>>,
-    curr_time: i32,
-    free_elves: &mut i32,
-) {
-    loop {
-        if *free_elves == 0 {
-            return;
-        }
-        match find_first_no_dep(deps) {
-            None => return,
-            Some(c) => {
-                let duration = (c as i32) - ('A' as i32) + 61;
-                let entry = events.entry(curr_time + duration).or_insert(Vec::new());
-                (*entry).push(c);
-                println!("{} from {} to {}", c, curr_time, curr_time + duration);
-                *free_elves -= 1;
-                deps.remove(&c);
-            }
-        }
-    }
+fn usage_error(msg: &str) -> ! {
+    eprintln!("{}\n\n{}", msg, USAGE);
+    process::exit(2);
 }
 
-fn complete_work(
-    events: &mut BTreeMap<i32, Vec<char>>,
-    deps: &mut BTreeMap<char, HashSet<char>>,
-    curr_time: &mut i32,
-    free_elves: &mut i32,
-) {
-    let to_remove = match events.iter().next() {
-        None => panic!("Nothing to do, not complete?!"),
-        Some((t, nodes)) => {
-            *curr_time = *t;
-            for c in nodes.iter() {
-                println!("{} finished at {}", c, t);
-
-                // Unblock the work...
-                for (_node, node_deps) in deps.iter_mut() {
-                    node_deps.remove(&c);
-                }
-                // and free the elf.
-                *free_elves += 1;
-            }
-            *t
-        }
-    };
-    events.remove(&to_remove);
+enum Output {
+    Answer,
+    Csv,
+    Json,
+    Gantt,
+    Critical,
 }
 
 fn main() {
-    let stdin = io::stdin();
-    let edges: Vec<_> = stdin
-        .lock()
-        .lines()
-        .map(|s| pair_parse(&s.unwrap()))
-        .collect();
-
-    // Build set of deps for each node.
-    let mut deps = BTreeMap::new();
-    for (before, after) in edges.iter() {
-        deps.entry(*before).or_insert(HashSet::new());
-        let after_entry = deps.entry(*after).or_insert(HashSet::new());
-        (*after_entry).insert(*before);
+    let mut config = Config::default();
+    let mut output = Output::Answer;
+    let mut scale = 1;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--workers" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => config.workers = n,
+                _ => usage_error("--workers needs a positive number"),
+            },
+            "--base" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n >= 0 => config.base = n,
+                _ => usage_error("--base needs a number, 0 or more"),
+            },
+            "--scale" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => scale = n,
+                _ => usage_error("--scale needs a positive number"),
+            },
+            "--durations" => {
+                let path = args.next().unwrap_or_else(|| usage_error("--durations needs a file"));
+                let text = fs::read_to_string(&path)
+                    .unwrap_or_else(|e| usage_error(&format!("Couldn't read {}: {}", path, e)));
+                let table = day07b::parse_durations(&text)
+                    .unwrap_or_else(|e| usage_error(&e.with_file(&path).to_string()));
+                config = config.with_table(table);
+            }
+            "--csv" => output = Output::Csv,
+            "--json" => output = Output::Json,
+            "--gantt" => output = Output::Gantt,
+            "--critical" => output = Output::Critical,
+            _ => usage_error(&format!("Unknown option: {}", arg)),
+        }
     }
 
-    // Use a fairly brute-force approach. At each time step, we
-    // keep assigning work until we're out of elves or work.
-    // Then we move time forward, complete work, etc.
-    let mut free_elves = 5;
-    let mut curr_time = 0;
-    let mut events = BTreeMap::new();
-
-    schedule_work(&mut events, &mut deps, curr_time, &mut free_elves);
-    while !deps.is_empty() {
-        complete_work(&mut events, &mut deps, &mut curr_time, &mut free_elves);
-        schedule_work(&mut events, &mut deps, curr_time, &mut free_elves);
+    let mut buffer = String::new();
+    io::stdin().read_to_string(&mut buffer).expect("Read error");
+    let res = day07b::parse(&buffer).and_then(|deps| match output {
+        Output::Critical => day07b::critical_path(&deps, &config).map(|path| format!("{}\n", path)),
+        _ => day07b::schedule(&deps, &config).map(|schedule| match output {
+            Output::Csv => schedule.to_csv(),
+            Output::Json => schedule.to_json(),
+            Output::Gantt => schedule.gantt(scale),
+            _ => format!("{}\n", schedule.finish()),
+        }),
+    });
+    match res {
+        Ok(out) => print!("{}", out),
+        Err(e) => {
+            eprintln!("{}", e.with_file("<stdin>"));
+            process::exit(1);
+        }
     }
-
-    print!("{}", events.keys().max().unwrap());
 }
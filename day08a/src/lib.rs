@@ -0,0 +1,225 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result, Scanner};
+use std::fmt;
+use std::mem;
+
+// A node of the license tree.
+//
+// The inputs are machine-generated, and can nest millions deep, so
+// everything here walks the tree with an explicit stack rather than
+// recursing. That includes cloning, dropping, comparing and printing
+// it, which is why none of those are derived.
+pub struct Node {
+    pub children: Vec<Node>,
+    pub metadata: Vec<usize>,
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        // Take the descendants out first, so that each node is empty
+        // by the time it's dropped.
+        let mut stack = mem::take(&mut self.children);
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Node) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some((a, b)) = stack.pop() {
+            if a.metadata != b.metadata || a.children.len() != b.children.len() {
+                return false;
+            }
+            stack.extend(a.children.iter().zip(b.children.iter()));
+        }
+        true
+    }
+}
+
+impl Eq for Node {}
+
+// As it would be derived.
+impl fmt::Debug for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // What's still to write, next last.
+        enum Part<'a> {
+            Start(&'a Node),
+            Comma,
+            End(&'a Node),
+        }
+        let mut stack = vec![Part::Start(self)];
+        while let Some(part) = stack.pop() {
+            match part {
+                Part::Start(node) => {
+                    write!(f, "Node {{ children: [")?;
+                    stack.push(Part::End(node));
+                    for (i, child) in node.children.iter().enumerate().rev() {
+                        stack.push(Part::Start(child));
+                        if i > 0 {
+                            stack.push(Part::Comma);
+                        }
+                    }
+                }
+                Part::Comma => write!(f, ", ")?,
+                Part::End(node) => write!(f, "], metadata: {:?} }}", node.metadata)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Clone for Node {
+    fn clone(&self) -> Node {
+        self.fold(|node, children| Node {
+            children,
+            metadata: node.metadata.clone(),
+        })
+    }
+}
+
+// Read a number, saying what it was for if the stream has run out.
+fn read_number(s: &mut Scanner, what: &str) -> ParseResult<usize> {
+    s.skip_whitespace();
+    if s.at_end() {
+        return Err(s.error(what));
+    }
+    s.number()
+}
+
+// A node that's still being read.
+struct Partial {
+    num_children: usize,
+    num_metadata: usize,
+    children: Vec<Node>,
+}
+
+fn read_header(s: &mut Scanner) -> ParseResult<Partial> {
+    let num_children = read_number(s, "the number of children")?;
+    let num_metadata = read_number(s, "the number of metadata entries")?;
+    Ok(Partial {
+        num_children,
+        num_metadata,
+        children: Vec::new(),
+    })
+}
+
+fn read_tree(s: &mut Scanner) -> ParseResult<Node> {
+    // The nodes from the root down to the one being read.
+    let mut stack = vec![read_header(s)?];
+    loop {
+        let top = stack.last_mut().unwrap();
+        if top.children.len() < top.num_children {
+            let child = read_header(s)?;
+            stack.push(child);
+            continue;
+        }
+
+        // All the children are in, so the metadata's next.
+        let partial = stack.pop().unwrap();
+        let mut metadata = Vec::new();
+        for _ in 0..partial.num_metadata {
+            metadata.push(read_number(s, "a metadata entry")?);
+        }
+        let node = Node {
+            children: partial.children,
+            metadata,
+        };
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => return Ok(node),
+        }
+    }
+}
+
+impl Node {
+    // Combine each node's children's results with its own, from the
+    // leaves up.
+    fn fold<T, F>(&self, f: F) -> T
+    where
+        F: Fn(&Node, Vec<T>) -> T,
+    {
+        // Each node being visited, with its children's results so far.
+        let mut stack = vec![(self, Vec::new())];
+        loop {
+            let (node, results) = stack.last().unwrap();
+            if results.len() < node.children.len() {
+                let child = &node.children[results.len()];
+                stack.push((child, Vec::new()));
+                continue;
+            }
+            let (node, results) = stack.pop().unwrap();
+            let result = f(node, results);
+            match stack.last_mut() {
+                Some((_, parent_results)) => parent_results.push(result),
+                None => return result,
+            }
+        }
+    }
+
+    // Part a: All the metadata, added up.
+    pub fn metadata_sum(&self) -> usize {
+        self.fold(|node, sums| sums.iter().sum::<usize>() + node.metadata.iter().sum::<usize>())
+    }
+
+    // Part b: Leaves are worth their metadata. Other nodes are worth
+    // the children their metadata picks out, counting from 1, and
+    // ignoring entries with no child.
+    pub fn value(&self) -> usize {
+        self.fold(|node, values| {
+            if node.children.is_empty() {
+                return node.metadata.iter().sum();
+            }
+            node.metadata
+                .iter()
+                .filter_map(|&i| i.checked_sub(1).and_then(|i| values.get(i)))
+                .sum()
+        })
+    }
+
+    // Back to the flat list of numbers it was read from.
+    pub fn serialize(&self) -> String {
+        let mut numbers = Vec::new();
+        // Nodes to write out, and whether it's their header (before the
+        // children) or their metadata (after).
+        let mut stack = vec![(self, true)];
+        while let Some((node, header)) = stack.pop() {
+            if header {
+                numbers.push(node.children.len().to_string());
+                numbers.push(node.metadata.len().to_string());
+                stack.push((node, false));
+                stack.extend(node.children.iter().rev().map(|child| (child, true)));
+            } else {
+                numbers.extend(node.metadata.iter().map(|m| m.to_string()));
+            }
+        }
+        numbers.join(" ")
+    }
+
+    // A line per node, indented by depth, showing its metadata.
+    pub fn dump(&self) -> String {
+        let mut out = String::new();
+        let mut stack = vec![(self, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            let metadata = node.metadata.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+            out += &"  ".repeat(depth);
+            out += &format!("- [{}]\n", metadata.join(" "));
+            stack.extend(node.children.iter().rev().map(|child| (child, depth + 1)));
+        }
+        out
+    }
+}
+
+pub fn parse(input: &str) -> Result<Node> {
+    // The tree is all on one line.
+    Ok(scan::parse_single(input, "a tree", read_tree)?)
+}
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    Ok(parse(input)?.metadata_sum())
+}
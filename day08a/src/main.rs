@@ -1,33 +1,34 @@
+extern crate day08a;
+
+use std::env;
 use std::io;
 use std::io::Read;
+use std::process;
 
-fn read_node<I>(iter: &mut I) -> i32
-where
-    I: Iterator<Item=i32> {
+const USAGE: &str = "Usage: day08a [--tree] < input
 
-    let num_children = iter.next().unwrap();
-    let num_metadata = iter.next().unwrap();
+--tree prints the license tree, a node per line, instead of the answer.";
 
-    let mut sum = 0;
-    for _ in 0..num_children {
-        sum += read_node(iter);
-    }
-    for _ in 0..num_metadata {
-        sum += iter.next().unwrap();
+fn main() {
+    let mut tree = false;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--tree" => tree = true,
+            _ => {
+                eprintln!("Unknown option: {}\n\n{}", arg, USAGE);
+                process::exit(2);
+            }
+        }
     }
-    sum
-}
 
-fn main() {
     let mut buffer = String::new();
     io::stdin().read_to_string(&mut buffer).expect("Read error");
-    let mut parts = buffer
-        .split(' ')
-        .filter(|s| s != &"")
-        .map(|s| s.trim().parse::<i32>().expect("Parse error"));
-
-    println!("{}", read_node(&mut parts));
-    if parts.next() != None {
-        panic!("Trailing cruft");
+    match day08a::parse(&buffer) {
+        Ok(root) if tree => print!("{}", root.dump()),
+        Ok(root) => println!("{}", root.metadata_sum()),
+        Err(e) => {
+            eprintln!("{}", e.with_file("<stdin>"));
+            process::exit(1);
+        }
     }
 }
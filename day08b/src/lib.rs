@@ -0,0 +1,10 @@
+extern crate aoc_common;
+extern crate day08a;
+
+use aoc_common::Result;
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    Ok(day08a::parse(input)?.value())
+}
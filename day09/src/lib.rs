@@ -0,0 +1,346 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{Error, ParseResult, Result};
+
+// Rather than keep the circle of marbles, which takes memory in
+// proportion to the number of marbles, work out the marbles removed
+// from the way the game repeats every 23 marbles.
+//
+// Think of the circle as a queue starting just clockwise of the
+// current marble. Placing a marble takes the marble from the front,
+// and puts it on the back followed by the new one. Every 23rd marble
+// instead pulls the last 8 marbles off the back: the first is removed
+// and scores, the second is put back, and the other 6 go on the front,
+// to be taken by the next 6 marbles.
+//
+// So, writing down everything put on the back in order (leaving out
+// what's pulled off again), the kth block of 23 marbles writes 37
+// entries: for each of its first 18 marbles, the marble taken from
+// the front and then the new marble, then its 19th marble. The
+// marbles taken from the front come from further back in that same
+// list, so any entry can be traced back to a marble number, through
+// entries a little under half as far along each time. The marble
+// scored by the kth block was entry 16k + 18.
+
+// How many of the earliest entries to keep, so tracing back stops
+// early. It's a fixed amount, however long the game.
+const CACHE_SIZE: usize = 1 << 20;
+
+struct Entries {
+    cache: Vec<usize>,
+}
+
+impl Entries {
+    fn new(len: usize) -> Entries {
+        let mut entries = Entries { cache: Vec::with_capacity(len) };
+        for i in 0..len {
+            // Only looks at the entries before it.
+            let entry = entries.get(i);
+            entries.cache.push(entry);
+        }
+        entries
+    }
+
+    fn get(&self, mut i: usize) -> usize {
+        loop {
+            if i < self.cache.len() {
+                return self.cache[i];
+            }
+            // Entry 0 is the first marble, on its own.
+            if i == 0 {
+                return 0;
+            }
+            let (k, offset) = ((i - 1) / 37, (i - 1) % 37);
+            let base = 23 * k;
+            if offset == 36 {
+                return base + 19;
+            }
+            // The jth marble of the block, and what it took from the
+            // front before it.
+            let j = offset / 2 + 1;
+            if offset % 2 == 1 {
+                return base + j;
+            }
+            i = match j {
+                // In the first block, and after the first 6 marbles of
+                // the others, what's taken comes from the list.
+                _ if k == 0 || j >= 7 => 16 * k + j - 1,
+                // The first 6 take what the last block pulled off the
+                // back: what its last 3 marbles took, and themselves.
+                1 | 3 | 5 => 16 * k + 3 + j / 2,
+                _ => return base + j / 2 - 4,
+            };
+        }
+    }
+}
+
+// The score of each player, with players numbered from 0 in the order
+// they play.
+pub fn play(players: usize, last_marble: usize) -> Vec<usize> {
+    let blocks = last_marble / 23;
+    let entries = Entries::new(CACHE_SIZE.min(16 * blocks));
+    let mut scores = vec![0; players];
+    for k in 0..blocks {
+        let marble = 23 * (k + 1);
+        // The player who plays the first marble is player 0.
+        scores[(marble - 1) % players] += marble + entries.get(16 * k + 18);
+    }
+    scores
+}
+
+// The knobs of the game. Every "multiple"th marble is kept by the
+// player, who also takes the marble "back" places counter-clockwise of
+// the current one, and the marble clockwise of that becomes current.
+// Any other marble goes just clockwise of the marble "insert_after"
+// places clockwise of the current one, and becomes current.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub multiple: usize,
+    pub back: usize,
+    pub insert_after: usize,
+}
+
+// The puzzle's rules.
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            multiple: 23,
+            back: 7,
+            insert_after: 1,
+        }
+    }
+}
+
+impl Rules {
+    // A multiple of 1 would take out the only marble on the first
+    // turn, leaving no circle.
+    fn check(&self) -> Result<()> {
+        if self.multiple < 2 {
+            return Err(Error::invalid("Scoring every marble leaves no circle to play in"));
+        }
+        Ok(())
+    }
+}
+
+// The same, under any rules. Only the puzzle's rules have a closed
+// form, so others are played out.
+pub fn play_with(players: usize, last_marble: usize, rules: &Rules) -> Vec<usize> {
+    if *rules == Rules::default() {
+        return play(players, last_marble);
+    }
+    let mut scores = vec![0; players];
+    for turn in Game::new(players, last_marble).with_rules(*rules) {
+        scores[turn.player] += turn.score;
+    }
+    scores
+}
+
+// What happened on one turn of the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Turn {
+    // Turns are numbered from 1.
+    pub turn: usize,
+    pub player: usize,
+    pub marble: usize,
+    // What the player scored this turn.
+    pub score: usize,
+    // The circle afterwards, clockwise from marble 0, if asked for.
+    pub circle: Option<Vec<usize>>,
+}
+
+// The game played out a turn at a time, keeping the circle as
+// next/prev pointers into an array indexed by marble. It's a lot
+// slower than "play", and needs memory for every marble, but shows
+// what's going on.
+pub struct Game {
+    players: usize,
+    last_marble: usize,
+    next: Vec<usize>,
+    prev: Vec<usize>,
+    current: usize,
+    snapshots: bool,
+    rules: Rules,
+}
+
+impl Game {
+    pub fn new(players: usize, last_marble: usize) -> Game {
+        assert!(players > 0, "Need at least one player");
+        Game {
+            players,
+            last_marble,
+            next: vec![0],
+            prev: vec![0],
+            current: 0,
+            snapshots: false,
+            rules: Rules::default(),
+        }
+    }
+
+    // Have each turn include the whole circle.
+    pub fn with_snapshots(self) -> Game {
+        Game {
+            snapshots: true,
+            ..self
+        }
+    }
+
+    pub fn with_rules(self, rules: Rules) -> Game {
+        assert!(rules.multiple >= 2, "Scoring every marble leaves no circle");
+        Game { rules, ..self }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    // How many marbles are in the circle. Every scoring marble is kept
+    // out of it, and takes another out with it.
+    pub fn circle_len(&self) -> usize {
+        let placed = self.next.len();
+        placed - 2 * ((placed - 1) / self.rules.multiple)
+    }
+
+    // Clockwise from marble 0.
+    pub fn circle(&self) -> Vec<usize> {
+        let mut circle = vec![0];
+        let mut marble = self.next[0];
+        while marble != 0 {
+            circle.push(marble);
+            marble = self.next[marble];
+        }
+        circle
+    }
+
+    // Up to "radius" marbles either side of the current one, which is
+    // in brackets.
+    pub fn render(&self, radius: usize) -> String {
+        let len = self.circle_len();
+        let before = radius.min((len - 1) / 2);
+        let after = radius.min(len - 1 - before);
+        let mut marble = self.current;
+        for _ in 0..before {
+            marble = self.prev[marble];
+        }
+        let mut marbles = Vec::new();
+        for _ in 0..before + after + 1 {
+            if marble == self.current {
+                marbles.push(format!("({})", marble));
+            } else {
+                marbles.push(marble.to_string());
+            }
+            marble = self.next[marble];
+        }
+        marbles.join(" ")
+    }
+
+    // Place the next marble, returning the score.
+    fn place(&mut self) -> usize {
+        let marble = self.next.len();
+        // Removed marbles keep their slot in the arrays, pointing
+        // nowhere in particular.
+        self.next.push(0);
+        self.prev.push(0);
+
+        if marble.is_multiple_of(self.rules.multiple) {
+            let mut to_remove = self.current;
+            for _ in 0..self.rules.back {
+                to_remove = self.prev[to_remove];
+            }
+            let (before, after) = (self.prev[to_remove], self.next[to_remove]);
+            self.next[before] = after;
+            self.prev[after] = before;
+            self.current = after;
+            return marble + to_remove;
+        }
+
+        // Between the marbles 1 and 2 clockwise of the current one, in
+        // the puzzle.
+        let mut before = self.current;
+        for _ in 0..self.rules.insert_after {
+            before = self.next[before];
+        }
+        let after = self.next[before];
+        self.next[before] = marble;
+        self.prev[after] = marble;
+        self.prev[marble] = before;
+        self.next[marble] = after;
+        self.current = marble;
+        0
+    }
+}
+
+impl Iterator for Game {
+    type Item = Turn;
+
+    fn next(&mut self) -> Option<Turn> {
+        let marble = self.next.len();
+        if marble > self.last_marble {
+            return None;
+        }
+        let score = self.place();
+        Some(Turn {
+            turn: marble,
+            player: (marble - 1) % self.players,
+            marble,
+            score,
+            circle: if self.snapshots { Some(self.circle()) } else { None },
+        })
+    }
+}
+
+// Input is of the form "N players; last marble is worth M points".
+fn parse(input: &str) -> ParseResult<(usize, usize)> {
+    scan::parse_single(input, "a game description", |s| {
+        let players = s.number()?;
+        s.literal("players; last marble is worth")?;
+        let max_marble = s.number()?;
+        s.literal("points")?;
+        Ok((players, max_marble))
+    })
+}
+
+pub struct Answer {
+    pub scores: Vec<usize>,
+    pub high_score: usize,
+    // Part b: The same game, with a last marble 100 times larger.
+    pub high_score_x100: usize,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}\n{}", self.high_score, self.high_score_x100)
+    }
+}
+
+fn parse_game(input: &str) -> Result<(usize, usize)> {
+    let (players, max_marble) = parse(input)?;
+    if players == 0 {
+        return Err(Error::invalid("Need at least one player"));
+    }
+    Ok((players, max_marble))
+}
+
+// The game described by the input, to play out a turn at a time.
+pub fn game(input: &str) -> Result<Game> {
+    let (players, max_marble) = parse_game(input)?;
+    Ok(Game::new(players, max_marble))
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    solve_with(input, &Rules::default())
+}
+
+pub fn solve_with(input: &str, rules: &Rules) -> Result<Answer> {
+    rules.check()?;
+    let (players, max_marble) = parse_game(input)?;
+    let max_marble_x100 = max_marble
+        .checked_mul(100)
+        .ok_or_else(|| Error::invalid("The last marble is too big to play 100 times over"))?;
+    let scores = play_with(players, max_marble, rules);
+    Ok(Answer {
+        high_score: *scores.iter().max().unwrap(),
+        high_score_x100: *play_with(players, max_marble_x100, rules).iter().max().unwrap(),
+        scores,
+    })
+}
@@ -1,85 +1,78 @@
-fn insert(curr: &mut usize, next: &mut Vec<usize>, prev: &mut Vec<usize>) -> usize {
-    let val = next.len();
+extern crate aoc_common;
+extern crate day09;
 
-    if val % 23 == 0 {
-        // Special case time!
+use aoc_common::Result;
+use day09::Rules;
+use std::env;
+use std::io;
+use std::io::Read;
+use std::process;
 
-        let mut to_remove = *curr;
-        for _ in 0..7 {
-            to_remove = prev[to_remove];
-        }
-
-        next[prev[to_remove]] = next[to_remove];
-        prev[next[to_remove]] = prev[to_remove];
-        *curr = next[to_remove];
+const USAGE: &str = "Usage: day09 [--replay [--radius <n>]] [--multiple <n>] [--back <n>] [--insert-after <n>] < input
 
-        prev.push(0);
-        next.push(0);
+--replay plays the game out a turn at a time, showing the marbles
+around the current one (10 either side, unless given --radius).
 
-        return val + to_remove;
-    }
+The rules can be changed: every --multiple'th marble (23) scores,
+taking the marble --back places counter-clockwise (7), and others go
+just after the marble --insert-after places clockwise (1).";
 
-    // Get points either side of insertion point.
-    let before = next[*curr];
-    let after = next[before];
-    // Update them to point at our new node.
-    next[before] = val;
-    prev[after] = val;
-    // Fill in links of our new node.
-    prev.push(before);
-    next.push(after);
-    // Update pointer.
-    *curr = val;
-    0
+fn usage_error(msg: &str) -> ! {
+    eprintln!("{}\n\n{}", msg, USAGE);
+    process::exit(2);
 }
 
-// Could build an iterator, but I'm lazy.
-fn _print_cycle(next: &Vec<usize>) {
-    let mut curr = 0;
-    loop {
-        print!("{} ", curr);
-        curr = next[curr];
-        if curr == 0 {
-            println!("");
-            return;
+fn replay(input: &str, radius: usize, rules: Rules) -> Result<()> {
+    let mut game = day09::game(input)?.with_rules(rules);
+    while let Some(turn) = game.next() {
+        print!("{:>4} [{}] {}", turn.turn, turn.player + 1, game.render(radius));
+        if turn.score > 0 {
+            print!("  scores {}", turn.score);
         }
+        println!();
     }
+    Ok(())
 }
 
-fn play(players: usize, max_marble: usize) -> usize {
-    // Let's use vectors as next/prev pointers into an array, again.
-    let mut next = Vec::new();
-    let mut prev = Vec::new();
-
-    next.push(0);
-    prev.push(0);
-    let mut curr = 0;
-
-    let mut scores = vec![0; players];
+fn number(arg: &str, value: Option<String>) -> usize {
+    match value.and_then(|n| n.parse().ok()) {
+        Some(n) => n,
+        None => usage_error(&format!("{} needs a number", arg)),
+    }
+}
 
-    for i in 0..max_marble + 1 {
-        let score = insert(&mut curr, &mut next, &mut prev);
-        if score > 0 {
-            // println!("Score: {} to {}", score, i % players);
-            scores[i % players] += score;
+fn main() {
+    let mut replaying = false;
+    let mut radius = 10;
+    let mut rules = Rules::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--replay" => replaying = true,
+            "--radius" => radius = number(&arg, args.next()),
+            "--multiple" => rules.multiple = number(&arg, args.next()),
+            "--back" => rules.back = number(&arg, args.next()),
+            "--insert-after" => rules.insert_after = number(&arg, args.next()),
+            _ => usage_error(&format!("Unknown option: {}", arg)),
         }
-        // print_cycle(&next);
     }
 
-    let max_score = scores.iter().max().unwrap();
-    println!(
-        "Players: {} Max marble: {} High score: {}",
-        players, max_marble, max_score
-    );
-    *max_score
-}
+    if rules.multiple < 2 {
+        usage_error("--multiple must be at least 2");
+    }
 
-fn main() {
-    play(10, 1618);
-    play(13, 7999);
-    play(17, 1104);
-    play(21, 6111);
-    play(30, 5807);
-    play(430, 71588);
-    play(430, 7158800);
+    let mut buffer = String::new();
+    io::stdin().read_to_string(&mut buffer).expect("Read error");
+    let res = if replaying {
+        replay(&buffer, radius, rules)
+    } else {
+        day09::solve_with(&buffer, &rules).map(|answer| println!("{}", answer))
+    };
+    match res {
+        Ok(()) => (),
+        Err(e) => {
+            eprintln!("{}", e.with_file("<stdin>"));
+            process::exit(1);
+        }
+    }
 }
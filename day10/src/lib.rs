@@ -0,0 +1,142 @@
+
+extern crate aoc_common;
+
+use aoc_common::ocr;
+use aoc_common::scan;
+use aoc_common::{Error, ParseResult, Result, Scanner};
+
+// Read as i32s, but moved as i64s, which is enough to get every point
+// well past every other without overflowing.
+#[derive(Debug)]
+struct Point {
+    px: i64,
+    py: i64,
+    vx: i64,
+    vy: i64,
+}
+
+// The most cells "render" will draw, so points that never come
+// together give an error rather than an enormous picture.
+const MAX_CELLS: i64 = 1 << 20;
+
+// Lines look like "position=< 9,  1> velocity=< 0,  2>".
+fn parse_point(s: &mut Scanner) -> ParseResult<Point> {
+    s.literal("position=<")?;
+    let px = s.number::<i32>()?.into();
+    s.literal(",")?;
+    let py = s.number::<i32>()?.into();
+    s.literal("> velocity=<")?;
+    let vx = s.number::<i32>()?.into();
+    s.literal(",")?;
+    let vy = s.number::<i32>()?.into();
+    s.literal(">")?;
+    Ok(Point { px, py, vx, vy })
+}
+
+fn step_time(points: &[Point], time: i64) -> Vec<Point> {
+    let update = |p: &Point| Point {
+        px: p.px + time * p.vx,
+        py: p.py + time * p.vy,
+        vx: p.vx,
+        vy: p.vy,
+    };
+
+    points.iter().map(update).collect()
+}
+
+fn extents(points: &[Point]) -> (i64, i64) {
+    let min_x = points.iter().map(|p| p.px).min().unwrap();
+    let max_x = points.iter().map(|p| p.px).max().unwrap();
+    let min_y = points.iter().map(|p| p.py).min().unwrap();
+    let max_y = points.iter().map(|p| p.py).max().unwrap();
+    (max_x - min_x, max_y - min_y)
+}
+
+// The width plus height of the bounding box at the given time, without
+// moving the points. Each edge of the box is the furthest out of some
+// straight lines, so it's convex in time, which the area isn't.
+fn size_at(points: &[Point], time: i64) -> i64 {
+    let min_x = points.iter().map(|p| p.px + time * p.vx).min().unwrap();
+    let max_x = points.iter().map(|p| p.px + time * p.vx).max().unwrap();
+    let min_y = points.iter().map(|p| p.py + time * p.vy).min().unwrap();
+    let max_y = points.iter().map(|p| p.py + time * p.vy).max().unwrap();
+    (max_x - min_x) + (max_y - min_y)
+}
+
+// The first time the bounding box is at its smallest. Once the time is
+// past the box's starting size, every point that's faster in some
+// direction has overtaken every slower one, so the box only grows from
+// then on. The size being convex, binary search for the first second
+// that doesn't make it smaller.
+fn smallest_time(points: &[Point]) -> i64 {
+    let (width, height) = extents(points);
+    let (mut lo, mut hi) = (0, width.max(height));
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if size_at(points, mid + 1) >= size_at(points, mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    lo
+}
+
+fn render(points: &[Point]) -> String {
+    let origin_x = points.iter().map(|p| p.px).min().unwrap();
+    let origin_y = points.iter().map(|p| p.py).min().unwrap();
+    let width = points.iter().map(|p| p.px).max().unwrap() + 1 - origin_x;
+    let height = points.iter().map(|p| p.py).max().unwrap() + 1 - origin_y;
+
+    let mut display = vec!['.'; (width * height) as usize];
+    for p in points.iter() {
+        let x = p.px - origin_x;
+        let y = p.py - origin_y;
+        display[(y * width + x) as usize] = '#';
+    }
+
+    display
+        .chunks(width as usize)
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub struct Answer {
+    // The rendered message, one line per row.
+    pub message: String,
+    // The message read as text, if its letters are in a known font.
+    pub text: Result<String>,
+    // The time at which the message appears.
+    pub time: i64,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.text {
+            Ok(ref text) => write!(f, "{}\n{}\n{}", self.message, text, self.time),
+            Err(ref e) => write!(f, "{}\n({})\n{}", self.message, e, self.time),
+        }
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let points = scan::parse_lines(input, parse_point)?;
+    if points.is_empty() {
+        return Err(scan::unexpected_end(input, "a point").into());
+    }
+
+    let t = smallest_time(&points);
+    let message = step_time(&points, t);
+    let (width, height) = extents(&message);
+    if (width + 1).saturating_mul(height + 1) > MAX_CELLS {
+        return Err(Error::invalid("The points never come close enough to form a message"));
+    }
+
+    let message = render(&message);
+    Ok(Answer {
+        text: ocr::read(&message),
+        message,
+        time: t,
+    })
+}
@@ -0,0 +1,65 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::Result;
+
+fn score(serial: i32, x: usize, y: usize) -> i32 {
+    let (x, y) = ((x as i32) + 1, (y as i32) + 1);
+
+    let rack_id = x + 10;
+    let power_level = rack_id * y;
+    let power_level = power_level + serial;
+    let power_level = power_level * rack_id;
+    let power_level = (power_level / 100) % 10;
+    power_level - 5
+}
+
+pub struct Answer {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let serial = scan::parse_single(input, "a serial number", |s| s.number::<i32>())?;
+    const WIDTH: usize = 300;
+    const HEIGHT: usize = 300;
+    const SIZE: usize = 3;
+
+    // Build powers.
+    let mut grid = Vec::new();
+    for y in 0..HEIGHT {
+        let mut row = Vec::new();
+        for x in 0..WIDTH {
+            row.push(score(serial, x, y));
+        }
+       grid.push(row);
+    }
+
+    // Find highest power
+    let mut best_x = 0;
+    let mut best_y = 0;
+    let mut best_score = -1000000;
+    for y in 0..HEIGHT-SIZE+1 {
+        for x in 0..WIDTH-SIZE+1 {
+            let mut score = 0;
+            for dx in 0..SIZE {
+                for dy in 0..SIZE {
+                    score += grid[y + dy][x + dx];
+                }
+            }
+           if score > best_score {
+               best_score = score;
+               best_x = x;
+               best_y = y;
+           }
+        }
+    }
+
+    Ok(Answer { x: best_x + 1, y: best_y + 1 })
+}
@@ -0,0 +1,110 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::Result;
+
+fn get_power(serial: i32, x: usize, y: usize) -> i32 {
+    let (x, y) = ((x as i32) + 1, (y as i32) + 1);
+
+    let rack_id = x + 10;
+    let power_level = rack_id * y;
+    let power_level = power_level + serial;
+    let power_level = power_level * rack_id;
+    let power_level = (power_level / 100) % 10;
+    power_level - 5
+}
+
+// Build running sums, starting at 0.
+fn sum_row(row: &[i32]) -> Vec<i32> {
+    let mut res = Vec::new();
+    let mut sum = 0;
+    res.push(0);
+    for x in row.iter() {
+        sum += *x;
+        res.push(sum);
+    }
+    res
+}
+
+fn build_sum_grid(grid: &[Vec<i32>]) -> Vec<Vec<i32>> {
+    let width = grid[0].len();
+
+    // Generate a list of row sums.
+    let mut res = Vec::new();
+    res.push(vec![0; width + 1]);
+    for row in grid.iter() {
+        res.push(sum_row(row));
+    }
+
+    // And then generate running sums down the columns.
+    for y in 1..res.len() {
+        let prev_row = res[y - 1].clone();
+        for (cell, prev) in res[y].iter_mut().zip(prev_row.iter()) {
+            *cell += *prev;
+        }
+    }
+
+    res
+}
+
+fn get_score(grid: &[Vec<i32>], x: usize, y: usize, size: usize) -> i32 {
+    let score = grid[y + size][x + size] - grid[y][x + size] - grid[y + size][x] + grid[y][x];
+    // println!("Scoring {} {} {} -> {}", x, y, size, score);
+    score
+}
+
+pub struct Answer {
+    pub x: usize,
+    pub y: usize,
+    pub size: usize,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.size)
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let serial = scan::parse_single(input, "a serial number", |s| s.number::<i32>())?;
+    const WIDTH: usize = 300;
+    const HEIGHT: usize = 300;
+
+    // Build powers.
+    let mut grid = Vec::new();
+    for y in 0..HEIGHT {
+        let mut row = Vec::new();
+        for x in 0..WIDTH {
+            row.push(get_power(serial, x, y));
+        }
+        grid.push(row);
+    }
+
+    let sum_grid = build_sum_grid(&grid);
+
+    // Find highest power
+    let mut best_x = 0;
+    let mut best_y = 0;
+    let mut best_size = 0;
+    let mut best_score = -1000000;
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let biggest_square = std::cmp::min(HEIGHT - y, WIDTH - x);
+            for size in 1..biggest_square {
+                let score = get_score(&sum_grid, x, y, size);
+                if score > best_score {
+                    best_score = score;
+                    best_x = x;
+                    best_y = y;
+                    best_size = size;
+                }
+            }
+        }
+    }
+
+    Ok(Answer {
+        x: best_x + 1,
+        y: best_y + 1,
+        size: best_size,
+    })
+}
@@ -0,0 +1,130 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result, Scanner};
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::fmt;
+
+const PATTERN_SIZE: i32 = 5;
+const PATTERN_CENTRE: i32 = 2;
+const GENERATIONS: i32 = 20;
+
+struct State {
+    state: BTreeSet<i32>,
+}
+
+impl State {
+    fn from_str(str: &str) -> State {
+        State {
+            state: str
+                .chars()
+                .zip(0..)
+                .filter(|(c, _)| *c == '#')
+                .map(|(_, i)| i)
+                .collect(),
+        }
+    }
+
+    fn update(&self, plants: &HashSet<String>) -> State {
+        let mut next = BTreeSet::new();
+        let (min, max) = match (self.state.iter().next(), self.state.iter().next_back()) {
+            (Some(min), Some(max)) => (*min - PATTERN_SIZE, *max),
+            // No plants, no new plants.
+            _ => return State { state: next },
+        };
+        for i in min..max {
+            let seq: String = (0..PATTERN_SIZE)
+                .map(|j| {
+                    if self.state.contains(&(i + j)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                }).collect();
+            // println!("{}", seq);
+            if plants.contains(&seq) {
+                next.insert(i + PATTERN_CENTRE);
+            }
+        }
+        State { state: next }
+    }
+
+    fn score(&self) -> i32 {
+        self.state.iter().sum()
+    }
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let min = *self.state.iter().next().unwrap();
+        let max = *self.state.iter().next_back().unwrap();
+        write!(f, "{}-{} ", min, max)?;
+        for i in min..max + 1 {
+            write!(f, "{}", if self.state.contains(&i) { '#' } else { '.' })?;
+        }
+        Ok(())
+    }
+}
+
+// Read a run of pots, each '#' or '.'.
+fn pots<'a>(s: &mut Scanner<'a>) -> ParseResult<&'a str> {
+    s.skip_whitespace();
+    let rest = s.rest();
+    let len = rest.find(|c| c != '#' && c != '.').unwrap_or(rest.len());
+    if len == 0 {
+        return Err(s.error("pots ('#' or '.')"));
+    }
+    for _ in 0..len {
+        s.next_char();
+    }
+    Ok(&rest[..len])
+}
+
+// Input is an initial state line, then the rules. We only keep the
+// patterns that produce a plant.
+fn parse(input: &str) -> ParseResult<(State, HashSet<String>)> {
+    let mut lines = scan::lines(input);
+    let mut s = match lines.next() {
+        Some(s) => s,
+        None => return Err(scan::unexpected_end(input, "the initial state")),
+    };
+    s.literal("initial state:")?;
+    let init = pots(&mut s)?;
+    s.end()?;
+
+    let mut plants = HashSet::new();
+    for mut s in lines {
+        if s.rest().trim().is_empty() {
+            continue;
+        }
+        s.skip_whitespace();
+        let column = s.column();
+        let pattern = pots(&mut s)?;
+        if pattern.len() != PATTERN_SIZE as usize {
+            return Err(s.error_at(column, "a pattern of 5 pots", pattern));
+        }
+        s.literal("=>")?;
+        s.skip_whitespace();
+        let column = s.column();
+        let result = pots(&mut s)?;
+        if result.len() != 1 {
+            return Err(s.error_at(column, "a single pot", result));
+        }
+        s.end()?;
+        if result == "#" {
+            plants.insert(pattern.to_string());
+        }
+    }
+    Ok((State::from_str(init), plants))
+}
+
+pub type Answer = i32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let (mut state, map) = parse(input)?;
+    for _ in 0..GENERATIONS {
+        state = state.update(&map);
+    }
+    Ok(state.score())
+}
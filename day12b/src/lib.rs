@@ -0,0 +1,169 @@
+// In the long run, either the cellular automata is some kind of
+// annoying Turing machine simulator or something, or (more likely) it
+// goes to either a cycling pattern, or forms a glider, or a glider gun,
+// or something like that. We're expecting repetition, so originally I
+// printed a bunch of generations and then worked from there...
+//
+// Doing this shows it iterates out to a fixed pattern which then
+// glides, creating an arithmetic sequence of plant scores. So, we
+// step until the pattern glides, and then extrapolate.
+
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{Error, ParseResult, Result, Scanner};
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::fmt;
+
+const PATTERN_SIZE: i32 = 5;
+const PATTERN_CENTRE: i32 = 2;
+const GENERATIONS: i64 = 50000000000;
+// How long we're willing to wait for a glider to form.
+const MAX_SETTLE: i64 = 10000;
+
+struct State {
+    state: BTreeSet<i32>,
+}
+
+impl State {
+    fn from_str(str: &str) -> State {
+        State {
+            state: str
+                .chars()
+                .zip(0..)
+                .filter(|(c, _)| *c == '#')
+                .map(|(_, i)| i)
+                .collect(),
+        }
+    }
+
+    fn update(&self, plants: &HashSet<String>) -> State {
+        let mut next = BTreeSet::new();
+        let (min, max) = match (self.state.iter().next(), self.state.iter().next_back()) {
+            (Some(min), Some(max)) => (*min - PATTERN_SIZE, *max),
+            // No plants, no new plants.
+            _ => return State { state: next },
+        };
+        for i in min..max {
+            let seq: String = (0..PATTERN_SIZE)
+                .map(|j| {
+                    if self.state.contains(&(i + j)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                }).collect();
+            // println!("{}", seq);
+            if plants.contains(&seq) {
+                next.insert(i + PATTERN_CENTRE);
+            }
+        }
+        State { state: next }
+    }
+
+    fn score(&self) -> i64 {
+        self.state.iter().map(|i| *i as i64).sum()
+    }
+
+    // If this state is the previous one moved along, return how far.
+    fn shift_from(&self, prev: &State) -> Option<i64> {
+        if self.state.len() != prev.state.len() {
+            return None;
+        }
+        let shift = *self.state.iter().next()? - *prev.state.iter().next()?;
+        if self.state.iter().zip(prev.state.iter()).all(|(a, b)| a - b == shift) {
+            Some(shift as i64)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let min = *self.state.iter().next().unwrap();
+        let max = *self.state.iter().next_back().unwrap();
+        write!(f, "{}-{} ", min, max)?;
+        for i in min..max + 1 {
+            write!(f, "{}", if self.state.contains(&i) { '#' } else { '.' })?;
+        }
+        Ok(())
+    }
+}
+
+// Read a run of pots, each '#' or '.'.
+fn pots<'a>(s: &mut Scanner<'a>) -> ParseResult<&'a str> {
+    s.skip_whitespace();
+    let rest = s.rest();
+    let len = rest.find(|c| c != '#' && c != '.').unwrap_or(rest.len());
+    if len == 0 {
+        return Err(s.error("pots ('#' or '.')"));
+    }
+    for _ in 0..len {
+        s.next_char();
+    }
+    Ok(&rest[..len])
+}
+
+// Input is an initial state line, then the rules. We only keep the
+// patterns that produce a plant.
+fn parse(input: &str) -> ParseResult<(State, HashSet<String>)> {
+    let mut lines = scan::lines(input);
+    let mut s = match lines.next() {
+        Some(s) => s,
+        None => return Err(scan::unexpected_end(input, "the initial state")),
+    };
+    s.literal("initial state:")?;
+    let init = pots(&mut s)?;
+    s.end()?;
+
+    let mut plants = HashSet::new();
+    for mut s in lines {
+        if s.rest().trim().is_empty() {
+            continue;
+        }
+        s.skip_whitespace();
+        let column = s.column();
+        let pattern = pots(&mut s)?;
+        if pattern.len() != PATTERN_SIZE as usize {
+            return Err(s.error_at(column, "a pattern of 5 pots", pattern));
+        }
+        s.literal("=>")?;
+        s.skip_whitespace();
+        let column = s.column();
+        let result = pots(&mut s)?;
+        if result.len() != 1 {
+            return Err(s.error_at(column, "a single pot", result));
+        }
+        s.end()?;
+        if result == "#" {
+            plants.insert(pattern.to_string());
+        }
+    }
+    Ok((State::from_str(init), plants))
+}
+
+pub type Answer = i64;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let (mut state, map) = parse(input)?;
+    let mut generation = 0;
+    loop {
+        if state.state.is_empty() {
+            return Ok(0);
+        }
+        if generation == MAX_SETTLE {
+            return Err(Error::invalid("The plants never settle into a glider"));
+        }
+        let next = state.update(&map);
+        generation += 1;
+        // Once the pattern just glides along, the score increases by
+        // the same amount each generation.
+        if let Some(shift) = next.shift_from(&state) {
+            let delta = shift * next.state.len() as i64;
+            return Ok(next.score() + (GENERATIONS - generation) * delta);
+        }
+        state = next;
+    }
+}
@@ -0,0 +1,187 @@
+extern crate aoc_common;
+
+use aoc_common::{Error, Grid, Result};
+use std::collections::HashSet;
+
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Copy)]
+enum Facing {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+const TURN_LEFT_NEXT: i32 = 0;
+const GO_STRAIGHT_NEXT: i32 = 1;
+const TURN_RIGHT_NEXT: i32 = 2;
+
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
+// NB: Field ordering used for sorting into correct update order.
+struct Train {
+    y: isize,
+    x: isize,
+    facing: Facing,
+    step: i32,
+}
+
+fn extract_trains(grid: &mut Grid<char>) -> Vec<Train> {
+    // Find all the trains.
+    let mut trains = Vec::new();
+    for ((x, y), c) in grid.iter() {
+        match c {
+            'v' => {
+                trains.push(Train {
+                    y,
+                    x,
+                    facing: Facing::Down,
+                    step: TURN_LEFT_NEXT,
+                });
+            }
+            '^' => {
+                trains.push(Train {
+                    y,
+                    x,
+                    facing: Facing::Up,
+                    step: TURN_LEFT_NEXT,
+                });
+            }
+            '<' => {
+                trains.push(Train {
+                    y,
+                    x,
+                    facing: Facing::Left,
+                    step: TURN_LEFT_NEXT,
+                });
+            }
+            '>' => {
+                trains.push(Train {
+                    y,
+                    x,
+                    facing: Facing::Right,
+                    step: TURN_LEFT_NEXT,
+                });
+            }
+            // Track.
+            _ => {}
+        }
+    }
+
+    // Remove all the trains from the grid to simplify processing...
+    for train in trains.iter() {
+        let replacement = match train.facing {
+            Facing::Right => '-',
+            Facing::Down => '|',
+            Facing::Left => '-',
+            Facing::Up => '|',
+        };
+        grid[(train.x, train.y)] = replacement;
+    }
+
+    trains
+}
+
+fn _print_track(grid: &Grid<char>) {
+    println!("{}", grid.render(|c| *c));
+}
+
+fn new_facing(facing: Facing, step: i32) -> Facing {
+    match (step, facing) {
+        (TURN_LEFT_NEXT, Facing::Right) => Facing::Up,
+        (TURN_LEFT_NEXT, Facing::Down) => Facing::Right,
+        (TURN_LEFT_NEXT, Facing::Left) => Facing::Down,
+        (TURN_LEFT_NEXT, Facing::Up) => Facing::Left,
+        (GO_STRAIGHT_NEXT, facing) => facing,
+        (TURN_RIGHT_NEXT, Facing::Right) => Facing::Down,
+        (TURN_RIGHT_NEXT, Facing::Down) => Facing::Left,
+        (TURN_RIGHT_NEXT, Facing::Left) => Facing::Up,
+        (TURN_RIGHT_NEXT, Facing::Up) => Facing::Right,
+        (_, _) => panic!("Nope, shouldn't happen"),
+    }
+}
+
+// Returns the location of the collision, if there is one.
+fn step_trains(grid: &Grid<char>, trains: &mut [Train]) -> Result<Option<(isize, isize)>> {
+    trains.sort();
+
+    let mut locs = trains.iter().map(|t| (t.x, t.y)).collect::<HashSet<_>>();
+
+    for train in trains.iter_mut() {
+        // Update position
+        {
+            let mut x = train.x;
+            let mut y = train.y;
+            match train.facing {
+                Facing::Right => x += 1,
+                Facing::Down => y += 1,
+                Facing::Left => x -= 1,
+                Facing::Up => y -= 1,
+            }
+            if locs.contains(&(x, y)) {
+                return Ok(Some((x, y)));
+            }
+            locs.remove(&(train.x, train.y));
+            train.x = x;
+            train.y = y;
+            locs.insert((x, y));
+        }
+
+        match (grid.get(train.x, train.y).cloned().unwrap_or(' '), train.facing) {
+            (' ', _) => return Err(Error::invalid("A cart ran off the end of the track")),
+            ('-', _) => {}
+            ('|', _) => {}
+
+            ('/', Facing::Left) => train.facing = Facing::Down,
+            ('/', Facing::Right) => train.facing = Facing::Up,
+            ('/', Facing::Up) => train.facing = Facing::Right,
+            ('/', Facing::Down) => train.facing = Facing::Left,
+
+            ('\\', Facing::Left) => train.facing = Facing::Up,
+            ('\\', Facing::Right) => train.facing = Facing::Down,
+            ('\\', Facing::Up) => train.facing = Facing::Left,
+            ('\\', Facing::Down) => train.facing = Facing::Right,
+
+            ('+', f) => {
+                train.facing = new_facing(f, train.step);
+                train.step = (train.step + 1) % 3;
+            }
+
+            (c, _) => {
+                panic!("Unexpected state: {:?} on {}", train, c);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+pub struct Answer {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut grid = Grid::parse_padded(input, "track or a cart", ' ', |c| {
+        if "-|/\\+<>^v ".contains(c) {
+            Some(c)
+        } else {
+            None
+        }
+    })?;
+
+    let mut trains = extract_trains(&mut grid);
+    if trains.len() < 2 {
+        return Err(Error::invalid("Need at least two carts for a crash"));
+    }
+
+    loop {
+        if let Some((x, y)) = step_trains(&grid, &mut trains)? {
+            return Ok(Answer { x: x as usize, y: y as usize });
+        }
+    }
+}
@@ -0,0 +1,182 @@
+extern crate aoc_common;
+
+use aoc_common::{Error, Grid, Result};
+use std::collections::HashMap;
+
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Copy)]
+enum Facing {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+const TURN_LEFT_NEXT: i32 = 0;
+const GO_STRAIGHT_NEXT: i32 = 1;
+const TURN_RIGHT_NEXT: i32 = 2;
+
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
+// NB: Field ordering used for sorting into correct update order.
+struct Train {
+    y: isize,
+    x: isize,
+    facing: Facing,
+    step: i32,
+}
+
+impl Train {
+    fn new(x: isize, y: isize, facing: Facing) -> Train {
+        Train {
+            y,
+            x,
+            facing,
+            step: TURN_LEFT_NEXT,
+        }
+    }
+}
+
+fn extract_trains(grid: &mut Grid<char>) -> Vec<Train> {
+    // Find all the trains.
+    let mut trains = Vec::new();
+    for ((x, y), c) in grid.iter() {
+        match c {
+            'v' => trains.push(Train::new(x, y, Facing::Down)),
+            '^' => trains.push(Train::new(x, y, Facing::Up)),
+            '<' => trains.push(Train::new(x, y, Facing::Left)),
+            '>' => trains.push(Train::new(x, y, Facing::Right)),
+            // Track.
+            _ => {}
+        }
+    }
+
+    // Remove all the trains from the grid to simplify processing...
+    for train in trains.iter() {
+        let replacement = match train.facing {
+            Facing::Right => '-',
+            Facing::Down => '|',
+            Facing::Left => '-',
+            Facing::Up => '|',
+        };
+        grid[(train.x, train.y)] = replacement;
+    }
+
+    trains
+}
+
+fn _print_track(grid: &Grid<char>) {
+    println!("{}", grid.render(|c| *c));
+}
+
+fn new_facing(facing: Facing, step: i32) -> Facing {
+    match (step, facing) {
+        (TURN_LEFT_NEXT, Facing::Right) => Facing::Up,
+        (TURN_LEFT_NEXT, Facing::Down) => Facing::Right,
+        (TURN_LEFT_NEXT, Facing::Left) => Facing::Down,
+        (TURN_LEFT_NEXT, Facing::Up) => Facing::Left,
+        (GO_STRAIGHT_NEXT, facing) => facing,
+        (TURN_RIGHT_NEXT, Facing::Right) => Facing::Down,
+        (TURN_RIGHT_NEXT, Facing::Down) => Facing::Left,
+        (TURN_RIGHT_NEXT, Facing::Left) => Facing::Up,
+        (TURN_RIGHT_NEXT, Facing::Up) => Facing::Right,
+        (_, _) => panic!("Nope, shouldn't happen"),
+    }
+}
+
+fn step_trains(grid: &Grid<char>, trains: &mut Vec<Train>) -> Result<()> {
+    trains.sort();
+
+    // Number of trains per location.
+    let mut locs = trains.iter().map(|t| ((t.x, t.y), 1)).collect::<HashMap<_, _>>();
+
+    for train in trains.iter_mut() {
+        // Update position
+        {
+            let mut x = train.x;
+            let mut y = train.y;
+
+            // Don't move a train if it's already crashed.
+            if *locs.get(&(x, y)).unwrap() > 1 {
+                continue;
+            }
+
+            match train.facing {
+                Facing::Right => x += 1,
+                Facing::Down => y += 1,
+                Facing::Left => x -= 1,
+                Facing::Up => y -= 1,
+            }
+
+            *(locs.get_mut(&(train.x, train.y)).unwrap()) -= 1;
+            train.x = x;
+            train.y = y;
+            *(locs.entry((x, y)).or_insert(0)) += 1;
+        }
+
+        match (grid.get(train.x, train.y).cloned().unwrap_or(' '), train.facing) {
+            (' ', _) => return Err(Error::invalid("A cart ran off the end of the track")),
+            ('-', _) => {}
+            ('|', _) => {}
+
+            ('/', Facing::Left) => train.facing = Facing::Down,
+            ('/', Facing::Right) => train.facing = Facing::Up,
+            ('/', Facing::Up) => train.facing = Facing::Right,
+            ('/', Facing::Down) => train.facing = Facing::Left,
+
+            ('\\', Facing::Left) => train.facing = Facing::Up,
+            ('\\', Facing::Right) => train.facing = Facing::Down,
+            ('\\', Facing::Up) => train.facing = Facing::Left,
+            ('\\', Facing::Down) => train.facing = Facing::Right,
+
+            ('+', f) => {
+                train.facing = new_facing(f, train.step);
+                train.step = (train.step + 1) % 3;
+            }
+
+            (c, _) => {
+                panic!("Unexpected state: {:?} on {}", train, c);
+            }
+        }
+    }
+
+    // Remove crashed trains.
+    trains.retain(|t| locs[&(t.x, t.y)] < 2);
+
+    Ok(())
+}
+
+pub struct Answer {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut grid = Grid::parse_padded(input, "track or a cart", ' ', |c| {
+        if "-|/\\+<>^v ".contains(c) {
+            Some(c)
+        } else {
+            None
+        }
+    })?;
+
+    let mut trains = extract_trains(&mut grid);
+    if trains.len() < 2 {
+        return Err(Error::invalid("Need at least two carts for a crash"));
+    }
+
+    // print_track(&grid);
+    // println!("{:?}", trains);
+    while trains.len() > 1 {
+        step_trains(&grid, &mut trains)?;
+        // println!("{} {:?}", trains.len(), trains);
+    }
+    match trains.first() {
+        Some(t) => Ok(Answer { x: t.x as usize, y: t.y as usize }),
+        None => Err(Error::invalid("Every cart crashed")),
+    }
+}
@@ -0,0 +1,39 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::Result;
+
+const DISPLAY: usize = 10;
+
+pub type Answer = String;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let count = scan::parse_single(input, "a recipe count", |s| s.number::<usize>())?;
+    let mut v = vec![3, 7];
+    let mut idx1 = 0;
+    let mut idx2 = 1;
+
+    let target = count + DISPLAY;
+
+    while v.len() < target {
+        let score1 = v[idx1];
+        let score2 = v[idx2];
+        let sum = score1 + score2;
+        if sum >= 10 {
+            v.push(sum / 10);
+        }
+        v.push(sum % 10);
+        idx1 = (idx1 + score1 + 1) % v.len();
+        idx2 = (idx2 + score2 + 1) % v.len();
+        // println!("{:?} {} {}", v, idx1, idx2);
+    }
+
+    while v.len() > target {
+        v.pop();
+    }
+
+    // Slightly messy way of getting last 10 while avoiding indexing
+    // calculations. :)
+    let items = v.iter().rev().take(DISPLAY).collect::<Vec<_>>();
+    Ok(items.iter().rev().map(|i| i.to_string()).collect())
+}
@@ -1,35 +1,17 @@
-const COUNT: usize = 580741;
-const DISPLAY: usize = 10;
+extern crate day14a;
 
-fn main() {
-    let mut v = vec![3, 7];
-    let mut idx1 = 0;
-    let mut idx2 = 1;
-
-    let target = COUNT + DISPLAY;
+use std::io;
+use std::io::Read;
+use std::process;
 
-    while v.len() < target {
-        let score1 = v[idx1];
-        let score2 = v[idx2];
-        let sum = score1 + score2;
-        if sum >= 10 {
-            v.push(sum / 10);
+fn main() {
+    let mut buffer = String::new();
+    io::stdin().read_to_string(&mut buffer).expect("Read error");
+    match day14a::solve(&buffer) {
+        Ok(answer) => println!("{}", answer),
+        Err(e) => {
+            eprintln!("{}", e.with_file("<stdin>"));
+            process::exit(1);
         }
-        v.push(sum % 10);
-        idx1 = (idx1 + score1 + 1) % v.len();
-        idx2 = (idx2 + score2 + 1) % v.len();
-        // println!("{:?} {} {}", v, idx1, idx2);
-    }
-
-    while v.len() > target {
-        v.pop();
-    }
-
-    // Slightly messy way of getting last 10 while avoiding indexing
-    // calculations. :)
-    let items = v.iter().rev().take(DISPLAY).collect::<Vec<_>>();
-    for i in items.iter().rev() {
-        print!("{}", i);
     }
-    println!("");
 }
@@ -0,0 +1,356 @@
+// Horrible hack up to just get this day's puzzle over with, as I'm
+// finding it tedious....
+
+extern crate aoc_common;
+
+use aoc_common::{Grid, Result};
+use std::collections::HashSet;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Species {
+    Elf,
+    Gnome,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct Unit {
+    species: Species,
+    attack: u32,
+    hp: u32,
+}
+
+impl Unit {
+    fn new(species: Species, elf_power: u32) -> Unit {
+        Unit {
+            species,
+            // NB: How they're attacked, not how they attack.
+            attack: if species == Species::Gnome { elf_power } else { 3},
+            hp: 200,
+        }
+    }
+}
+
+const SQUARES: &str = "'#', '.', 'E' or 'G'";
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Square {
+    Wall,
+    Space,
+    Unit(Unit),
+}
+
+impl Square {
+    fn from(c: char, elf_power: u32) -> Option<Square> {
+        match c {
+            '.' => Some(Square::Space),
+            '#' => Some(Square::Wall),
+            'E' => Some(Square::Unit(Unit::new(Species::Elf, elf_power))),
+            'G' => Some(Square::Unit(Unit::new(Species::Gnome, elf_power))),
+            _ => None,
+        }
+    }
+
+    // Only used when debugging.
+    #[allow(dead_code)]
+    fn disp(&self) -> char {
+        match self {
+            Square::Wall => '#',
+            Square::Space => '.',
+            Square::Unit(u) => match u.species {
+                Species::Elf => 'E',
+                Species::Gnome => 'G',
+            },
+        }
+    }
+}
+
+fn _print_grid(grid: &Grid<Square>) {
+    println!("{}", grid.render(Square::disp));
+}
+
+fn _print_units(grid: &Grid<Square>) {
+    for square in grid.cells() {
+        if let Square::Unit(u) = square {
+            println!("{:?}: {}", u.species, u.hp);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+enum Move {
+    Up,
+    Left,
+    Right,
+    Down,
+}
+
+impl Move {
+    fn get_species(grid: &Grid<Square>, x: isize, y: isize) -> Option<Species> {
+        if let Square::Unit(ref u) = grid[(x, y)] {
+            Some(u.species)
+        } else {
+            None
+        }
+    }
+
+    // We try to move *onto* the enemy, and then decide to never do
+    // that final move later. I *think* it would be equivalent to just
+    // find the top-left-est square next to an enemy, but I'm not sure if
+    // there's nasty corner case bugs or not, so I'm avoiding that
+    // optimisation.
+    fn can_move_to(square: &Square, target: Species) -> bool {
+        match square {
+            Square::Wall => false,
+            Square::Space => true,
+            Square::Unit(u) => u.species == target,
+        }
+    }
+
+    fn from_delta(dx: isize, dy: isize) -> Move {
+        match (dx, dy) {
+            (0, -1) => Move::Up,
+            (-1, 0) => Move::Left,
+            (1, 0) => Move::Right,
+            _ => Move::Down,
+        }
+    }
+
+    fn neighbours(
+        seen: &mut HashSet<(isize, isize)>,
+        grid: &Grid<Square>,
+        target: Species,
+        x: isize,
+        y: isize,
+    ) -> Vec<(isize, isize, Move)> {
+        // Neighbours come in reading order, so the moves are sorted.
+        let result = grid
+            .neighbours4(x, y)
+            .map(|(nx, ny)| (ny, nx, Move::from_delta(nx - x, ny - y)))
+            .filter(|(y, x, _)| {
+                Move::can_move_to(&grid[(*x, *y)], target) && !seen.contains(&(*x, *y))
+            }).collect::<Vec<_>>();
+        for (y, x, _) in result.iter() {
+            seen.insert((*x, *y));
+        }
+        result
+    }
+
+    fn find(grid: &Grid<Square>, x: isize, y: isize) -> Option<Move> {
+        // println!("Finding target for {} {}", x, y);
+        let target = match Move::get_species(grid, x, y).unwrap() {
+            Species::Gnome => Species::Elf,
+            Species::Elf => Species::Gnome,
+        };
+        // All squares we've already reached.
+        let mut seen = HashSet::new();
+        // All squares on the current distance frontier.
+        let mut frontier = Move::neighbours(&mut seen, grid, target, x, y);
+        while !frontier.is_empty() {
+            // println!("Frontier: {:?}", frontier);
+            {
+                let mut targets = frontier
+                    .iter()
+                    .filter(|(y, x, _)| Move::get_species(grid, *x, *y) == Some(target))
+                    .collect::<Vec<_>>();
+                if !targets.is_empty() {
+                    // println!("Targets: {:?}", targets);
+                    // We can reach some target. We'll choose the one
+                    // that's most top-left, and then tie break on most
+                    // top-left starting movement direction.
+                    targets.sort();
+                    // Return the direction to move.
+                    return Some(targets[0].2);
+                }
+            }
+
+            // No target reachable at this distance. Build the set of
+            // points we can reach. As the initial list of neighbours
+            // was sorted, we'll always favour the top-left starting
+            // direction.
+            let mut new_frontier = Vec::new();
+            for (y, x, original_move) in frontier.iter() {
+                for (new_y, new_x, _) in Move::neighbours(&mut seen, grid, target, *x, *y).iter() {
+                    new_frontier.push((*new_y, *new_x, *original_move));
+                }
+            }
+            // Is this O(1)? Who knows! I have much to learn about Rust.
+            std::mem::swap(&mut frontier, &mut new_frontier);
+        }
+        None
+    }
+}
+
+// Moves a unit and returns its new location
+fn move_unit(grid: &mut Grid<Square>, x: isize, y: isize) -> (isize, isize) {
+    let new_loc = match Move::find(grid, x, y) {
+        Some(Move::Up) => Some((x, y - 1)),
+        Some(Move::Left) => Some((x - 1, y)),
+        Some(Move::Right) => Some((x + 1, y)),
+        Some(Move::Down) => Some((x, y + 1)),
+        None => None,
+    };
+    // "find" is willing to move onto the enemy. Don't do that.
+    let new_loc = if let Some((x, y)) = new_loc {
+        if grid[(x, y)] == Square::Space {
+            Some((x, y))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    if let Some((new_x, new_y)) = new_loc {
+        // println!("{}, {} -> {}, {}", x, y, new_x, new_y);
+        grid[(new_x, new_y)] = grid[(x, y)];
+        grid[(x, y)] = Square::Space;
+        return (new_x, new_y);
+    }
+    (x, y)
+}
+
+// Performs combat.
+fn attack_with_unit(grid: &mut Grid<Square>, x: isize, y: isize) {
+    let target = match Move::get_species(grid, x, y).unwrap() {
+        Species::Gnome => Species::Elf,
+        Species::Elf => Species::Gnome,
+    };
+
+    let mut targets = grid
+        .neighbours4(x, y)
+        .map(|(x, y)| (grid[(x, y)], y, x))
+        .filter(|(sq, _, _)| if let Square::Unit(u) = sq {
+            u.species == target
+        } else {
+            false
+        })
+        .collect::<Vec<_>>();
+    // Sort is stable, so if HPs match we use position
+    targets.sort_by(|(a, _, _), (b, _, _)| if let (Square::Unit(au), Square::Unit(bu)) = (a, b) {
+        au.hp.cmp(&bu.hp)
+    } else {
+        panic!("Not a unit?!")
+    });
+    // println!("Targets: {:?}", targets);
+
+    // If there's a target, let's attack!
+    if let Some((_, ty, tx)) = targets.first() {
+        let target = &mut grid[(*tx, *ty)];
+        // println!("We have a target: {:?}", target);
+
+        // Not sure how to update through a reference, so make a copy
+        // of the struct and then copy it back...
+        if let Square::Unit(mut u) = target {
+            // Cheat: Rather than look up our AP, reduce by AP of target.
+            if u.hp <= u.attack {
+                 *target = Square::Space;
+            } else {
+                 u.hp -= u.attack;
+                 *target = Square::Unit(u);
+            }
+        }
+    }
+}
+
+// Run through the entire grid, moving pieces and doing combat. Returns
+// false if combat ended part-way through the round.
+fn update_all(grid: &mut Grid<Square>) -> bool {
+    // As we're updating the grid as we go, don't move the units we've
+    // already moved, if we scan over them again.
+    let mut moved_already = HashSet::new();
+
+    for (x, y) in grid.positions() {
+        if moved_already.contains(&(x, y)) {
+            continue;
+        }
+
+        if let Square::Unit(_) = grid[(x, y)] {
+            // Combat only ends when a unit finds no enemies on
+            // its turn, so the round doesn't count.
+            if is_complete(grid) {
+                return false;
+            }
+            let (x, y) = move_unit(grid, x, y);
+            moved_already.insert((x, y));
+            attack_with_unit(grid, x, y);
+        }
+    }
+    true
+}
+
+fn is_complete(grid: &Grid<Square>) -> bool {
+    let mut elves = 0;
+    let mut gnomes = 0;
+    for square in grid.cells() {
+        if let Square::Unit(u) = square {
+            if u.species == Species::Elf {
+                elves += 1;
+            } else {
+                gnomes +=1 ;
+            }
+        }
+    }
+    elves == 0 || gnomes == 0
+}
+
+// Only score if elves won!
+fn sum_hp(grid: &Grid<Square>) -> u32 {
+    let mut hp = 0;
+    for square in grid.cells() {
+        if let Square::Unit(u) = square {
+            hp += u.hp;
+        }
+    }
+    hp
+}
+
+// Count elves before and after, as it's easier than modifying the game loop
+// to early-out. *sigh*
+fn elf_count(grid: &Grid<Square>) -> u32 {
+    let mut count = 0;
+    for square in grid.cells() {
+        if let Square::Unit(u) = square {
+            if u.species == Species::Elf {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+// Run an attempt at the level with a given elf power.
+fn attempt(input: &str, elf_power: u32) -> Result<(u32, u32)> {
+    let mut grid = Grid::parse(input, SQUARES, |c| Square::from(c, elf_power))?;
+
+    let c = elf_count(&grid);
+
+//    print_grid(&grid);
+//    print_units(&grid);
+    // A unit has to see there's no enemies for the combat to end, so
+    // if the last unit killed the last enemy in a round, that round
+    // still counts. Only count full rounds.
+    let mut round = 0;
+    while update_all(&mut grid) {
+        round += 1;
+        // println!("\nRound {}", round);
+//        print_grid(&grid);
+//        print_units(&grid);
+    }
+
+    if c != elf_count(&grid) {
+        Ok((round, 0))
+    } else {
+        Ok((round, sum_hp(&grid)))
+    }
+}
+
+pub type Answer = u32;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    for elf_power in 3.. {
+        let (round, hp) = attempt(input, elf_power)?;
+        if hp > 0 {
+            return Ok(round * hp);
+        }
+    }
+    unreachable!()
+}
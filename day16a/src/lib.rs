@@ -0,0 +1,113 @@
+extern crate aoc_common;
+extern crate elfcode;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result, Scanner};
+use elfcode::samples;
+
+#[derive(Debug)]
+struct TestCase {
+    before: Vec<usize>,
+    after: Vec<usize>,
+    opcode: usize,
+    a: usize,
+    b: usize,
+    c: usize,
+}
+
+impl TestCase {
+    fn from(before: Vec<usize>, after: Vec<usize>, instr: &[usize]) -> TestCase {
+        TestCase {
+            before,
+            after,
+            opcode: instr[0],
+            a: instr[1],
+            b: instr[2],
+            c: instr[3],
+        }
+    }
+}
+
+// Registers look like "Before: [3, 2, 1, 1]".
+fn read_regs(s: &mut Scanner, label: &str) -> ParseResult<Vec<usize>> {
+    s.literal(label)?;
+    s.literal("[")?;
+    let mut regs = Vec::new();
+    for i in 0..4 {
+        if i > 0 {
+            s.literal(",")?;
+        }
+        regs.push(s.number()?);
+    }
+    s.literal("]")?;
+    Ok(regs)
+}
+
+// Instructions are "opcode a b c". In the puzzle every operand, even
+// an immediate one, is less than four, and relying on that keeps the
+// register accesses in range.
+fn read_instr(s: &mut Scanner) -> ParseResult<Vec<usize>> {
+    let mut instr = Vec::new();
+    for i in 0..4 {
+        let (limit, expected) = if i == 0 { (16, "an opcode (0-15)") } else { (4, "an operand (0-3)") };
+        s.skip_whitespace();
+        let column = s.column();
+        let n = s.number::<usize>()?;
+        if n >= limit {
+            return Err(s.error_at(column, expected, &n.to_string()));
+        }
+        instr.push(n);
+    }
+    Ok(instr)
+}
+
+fn is_sample(s: &Scanner) -> bool {
+    s.rest().trim_start().starts_with("Before")
+}
+
+// Read a sample's three lines: registers before, the instruction, and
+// registers after.
+fn read_sample<'a, I>(input: &str, first: Scanner<'a>, lines: &mut I) -> ParseResult<TestCase>
+where
+    I: Iterator<Item = Scanner<'a>>,
+{
+    let mut s = first;
+    let before = read_regs(&mut s, "Before:")?;
+    s.end()?;
+    let mut s = lines.next().ok_or_else(|| scan::unexpected_end(input, "an instruction"))?;
+    let instr = read_instr(&mut s)?;
+    s.end()?;
+    let mut s = lines.next().ok_or_else(|| scan::unexpected_end(input, "\"After:\""))?;
+    let after = read_regs(&mut s, "After:")?;
+    s.end()?;
+    Ok(TestCase::from(before, after, &instr))
+}
+
+// Count the number of opcodes that transform the state to match the test case.
+fn count_candidates(test_case: &TestCase) -> usize {
+    let instr = [test_case.opcode, test_case.a, test_case.b, test_case.c];
+    samples::fitting(&test_case.before, instr, &test_case.after).len()
+}
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut lines = scan::lines(input).filter(|s| !s.rest().trim().is_empty());
+
+    // Only the samples matter, so stop at the program.
+    let mut cases = Vec::new();
+    while let Some(s) = lines.next() {
+        if !is_sample(&s) {
+            break;
+        }
+        cases.push(read_sample(input, s, &mut lines)?);
+    }
+
+    let mut case_count = 0;
+    for c in cases.iter() {
+        if count_candidates(c) >= 3 {
+            case_count += 1;
+        }
+    }
+    Ok(case_count)
+}
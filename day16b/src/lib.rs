@@ -0,0 +1,186 @@
+extern crate aoc_common;
+extern crate elfcode;
+
+use aoc_common::scan;
+use aoc_common::{Error, ParseResult, Result, Scanner};
+use elfcode::{mapping, samples};
+use elfcode::{Instruction, Machine, Opcode, Program};
+use std::collections::BTreeSet;
+
+#[derive(Debug)]
+struct TestCase {
+    // The line of the sample's "Before:", for reporting.
+    line: usize,
+    before: Vec<usize>,
+    after: Vec<usize>,
+    opcode: usize,
+    a: usize,
+    b: usize,
+    c: usize,
+}
+
+impl TestCase {
+    fn from(line: usize, before: Vec<usize>, after: Vec<usize>, instr: &[usize]) -> TestCase {
+        TestCase {
+            line,
+            before,
+            after,
+            opcode: instr[0],
+            a: instr[1],
+            b: instr[2],
+            c: instr[3],
+        }
+    }
+}
+
+// Registers look like "Before: [3, 2, 1, 1]".
+fn read_regs(s: &mut Scanner, label: &str) -> ParseResult<Vec<usize>> {
+    s.literal(label)?;
+    s.literal("[")?;
+    let mut regs = Vec::new();
+    for i in 0..4 {
+        if i > 0 {
+            s.literal(",")?;
+        }
+        regs.push(s.number()?);
+    }
+    s.literal("]")?;
+    Ok(regs)
+}
+
+// Instructions are "opcode a b c". In the puzzle every operand, even
+// an immediate one, is less than four, and relying on that keeps the
+// register accesses in range.
+fn read_instr(s: &mut Scanner) -> ParseResult<Vec<usize>> {
+    let mut instr = Vec::new();
+    for i in 0..4 {
+        let (limit, expected) = if i == 0 { (16, "an opcode (0-15)") } else { (4, "an operand (0-3)") };
+        s.skip_whitespace();
+        let column = s.column();
+        let n = s.number::<usize>()?;
+        if n >= limit {
+            return Err(s.error_at(column, expected, &n.to_string()));
+        }
+        instr.push(n);
+    }
+    Ok(instr)
+}
+
+fn is_sample(s: &Scanner) -> bool {
+    s.rest().trim_start().starts_with("Before")
+}
+
+// Read a sample's three lines: registers before, the instruction, and
+// registers after.
+fn read_sample<'a, I>(input: &str, first: Scanner<'a>, lines: &mut I) -> ParseResult<TestCase>
+where
+    I: Iterator<Item = Scanner<'a>>,
+{
+    let mut s = first;
+    let line = s.line();
+    let before = read_regs(&mut s, "Before:")?;
+    s.end()?;
+    let mut s = lines.next().ok_or_else(|| scan::unexpected_end(input, "an instruction"))?;
+    let instr = read_instr(&mut s)?;
+    s.end()?;
+    let mut s = lines.next().ok_or_else(|| scan::unexpected_end(input, "\"After:\""))?;
+    let after = read_regs(&mut s, "After:")?;
+    s.end()?;
+    Ok(TestCase::from(line, before, after, &instr))
+}
+
+// The opcodes that would turn the sample's before into its after.
+fn fitting(test_case: &TestCase) -> BTreeSet<Opcode> {
+    let instr = [test_case.opcode, test_case.a, test_case.b, test_case.c];
+    samples::fitting(&test_case.before, instr, &test_case.after).into_iter().collect()
+}
+
+fn names(opcodes: &BTreeSet<Opcode>) -> String {
+    opcodes.iter().map(|op| op.name()).collect::<Vec<_>>().join("/")
+}
+
+// Enough to tell whether the mapping is unique.
+const MAPPING_LIMIT: usize = 2;
+
+fn build_mapping(cases: &[TestCase]) -> Result<(Vec<Opcode>, Vec<usize>)> {
+    let samples = cases.iter().map(|c| (c.opcode, fitting(c))).collect::<Vec<_>>();
+    let analysis = mapping::analyse(&samples, MAPPING_LIMIT);
+    let contradictory = analysis
+        .contradictory
+        .iter()
+        .map(|&i| cases[i].line)
+        .collect::<Vec<_>>();
+    if let Some(mapping) = analysis.unique() {
+        return Ok((mapping.to_vec(), contradictory));
+    }
+
+    let mut msg = if analysis.mappings.is_empty() {
+        "No mapping of opcodes fits the samples".to_string()
+    } else {
+        let ambiguous = analysis
+            .ambiguous()
+            .iter()
+            .map(|&n| format!("{} ({})", n, names(&analysis.possible[n])))
+            .collect::<Vec<_>>();
+        format!("The samples don't pin down opcodes {}", ambiguous.join(", "))
+    };
+    if !contradictory.is_empty() {
+        let lines = contradictory.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        msg += &format!("; contradictory samples on lines {}", lines.join(", "));
+    }
+    Err(Error::invalid(msg))
+}
+
+fn translate(mapping: &[Opcode], instrs: &[Vec<usize>]) -> Program {
+    let instrs = instrs
+        .iter()
+        .map(|instr| Instruction::new(mapping[instr[0]], instr[1], instr[2], instr[3]))
+        .collect();
+    Program::new(4, None, instrs)
+}
+
+pub struct Answer {
+    // Register 0 after running the program.
+    pub value: usize,
+    // The lines of samples that disagreed with the rest, and were
+    // ignored.
+    pub contradictory: Vec<usize>,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut lines = scan::lines(input)
+        .filter(|s| !s.rest().trim().is_empty())
+        .peekable();
+
+    // Read the test cases into "cases".
+    let mut cases = Vec::new();
+    while lines.peek().is_some_and(is_sample) {
+        let s = lines.next().unwrap();
+        cases.push(read_sample(input, s, &mut lines)?);
+    }
+
+    // Read the executable code into "code".
+    let code = lines
+        .map(|mut s| {
+            let instr = read_instr(&mut s)?;
+            s.end()?;
+            Ok(instr)
+        }).collect::<ParseResult<Vec<_>>>()?;
+
+    // Build the mapping.
+    let (mapping, contradictory) = build_mapping(&cases)?;
+    // And run the code with it...
+    let program = translate(&mapping, &code);
+    let mut machine = Machine::new(&program);
+    machine.run();
+    Ok(Answer {
+        value: machine.regs()[0],
+        contradictory,
+    })
+}
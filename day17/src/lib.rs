@@ -0,0 +1,246 @@
+
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{Error, Grid, ParseResult, Result, Scanner};
+
+const START_X: usize = 500;
+
+#[derive(Debug)]
+struct Line {
+    const_val: usize,
+    min_val: usize,
+    max_val: usize,
+    is_vertical: bool,
+}
+
+impl Line {
+    // Lines look like "x=495, y=2..7" or "y=7, x=495..501".
+    fn new(s: &mut Scanner) -> ParseResult<Line> {
+        let is_vertical = s.keyword(&["x", "y"])? == "x";
+        s.literal("=")?;
+        let const_val = s.number()?;
+        s.literal(",")?;
+        s.keyword(if is_vertical { &["y"] } else { &["x"] })?;
+        s.literal("=")?;
+        let min_val = s.number()?;
+        s.literal("..")?;
+        s.skip_whitespace();
+        let column = s.column();
+        let max_val: usize = s.number()?;
+        if max_val < min_val {
+            return Err(s.error_at(column, "a range end no less than the start", &max_val.to_string()));
+        }
+        Ok(Line {
+            const_val,
+            min_val,
+            max_val,
+            is_vertical,
+        })
+    }
+}
+
+// Returns x range, y range - inclusive in both direction.
+fn get_extents(lines: &[Line]) -> (usize, usize, usize, usize) {
+    let min_x = lines
+        .iter()
+        .map(|l| {
+            if l.is_vertical {
+                l.const_val
+            } else {
+                l.min_val
+            }
+        }).min().unwrap();
+    let max_x = lines
+        .iter()
+        .map(|l| {
+            if l.is_vertical {
+                l.const_val
+            } else {
+                l.max_val
+            }
+        }).max().unwrap();
+    let min_y = lines
+        .iter()
+        .map(|l| {
+            if !l.is_vertical {
+                l.const_val
+            } else {
+                l.min_val
+            }
+        }).min().unwrap();
+    let max_y = lines
+        .iter()
+        .map(|l| {
+            if !l.is_vertical {
+                l.const_val
+            } else {
+                l.max_val
+            }
+        }).max().unwrap();
+    (min_x, max_x, min_y, max_y)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Square {
+    Sand,
+    Clay,
+    Flowing,
+    Still,
+}
+
+struct Reservoir {
+    grid: Grid<Square>,
+}
+
+impl Reservoir {
+    fn new(extents: (usize, usize, usize, usize)) -> Reservoir {
+        let (min_x, max_x, min_y, max_y) = extents;
+        // Extend the level slightly to the sides to avoid the need
+        // for boundary checks on the sides.
+        let x_size = max_x - min_x + 5;
+        let y_size = max_y - min_y + 1;
+        Reservoir {
+            grid: Grid::with_origin(min_x as isize - 2, min_y as isize, x_size, y_size, Square::Sand),
+        }
+    }
+
+    fn sq(&mut self, x: isize, y: isize) -> &mut Square {
+        &mut self.grid[(x, y)]
+    }
+
+    fn add(&mut self, line: &Line) {
+        if line.is_vertical {
+            for y in line.min_val..line.max_val + 1 {
+                *self.sq(line.const_val as isize, y as isize) = Square::Clay;
+            }
+        } else {
+            for x in line.min_val..line.max_val + 1 {
+                *self.sq(x as isize, line.const_val as isize) = Square::Clay;
+            }
+        }
+    }
+
+    fn _print(&self) {
+        println!("{}", self.grid.render(|cell| match cell {
+            Square::Sand => '.',
+            Square::Clay => '#',
+            Square::Flowing => '|',
+            Square::Still => '~',
+        }));
+    }
+
+    // Pour water into the given square.
+    fn pour(&mut self, x: isize, y: isize) {
+        // Can only flow into sand.
+        if *self.sq(x, y) != Square::Sand {
+            return;
+        }
+        // Always flow out the bottom.
+        if y == self.grid.max_y() {
+            *self.sq(x, y) = Square::Flowing;
+            return;
+        }
+
+        // Flow left and right, favouring downwards
+        let mut outflowing = false;
+        let mut lx = x;
+        while *self.sq(lx, y) == Square::Sand {
+            *self.sq(lx, y) = Square::Flowing;
+            self.pour(lx, y + 1);
+            if *self.sq(lx, y + 1) == Square::Flowing {
+                outflowing = true;
+                break;
+            }
+            lx -= 1;
+        }
+        let mut rx = x;
+        // Messy corner-case-ness. Can't think how to make this pretty.
+        *self.sq(rx, y) = Square::Sand;
+        while *self.sq(rx, y) == Square::Sand {
+            *self.sq(rx, y) = Square::Flowing;
+            self.pour(rx, y + 1);
+            if *self.sq(rx, y + 1) == Square::Flowing {
+                outflowing = true;
+                break;
+            }
+            rx += 1;
+        }
+
+        if !outflowing {
+            for sx in lx + 1..rx {
+                *self.sq(sx, y) = Square::Still;
+            }
+        }
+
+        // self._print();
+        // println!("");
+    }
+
+    fn count_water_row(row: &[Square]) -> usize {
+        row.iter().map(|x| if *x == Square::Flowing || *x == Square::Still {
+                1
+            } else {
+                0
+            })
+            .sum()
+    }
+
+    fn count_water(&self) -> usize {
+        self.grid.rows().map(Reservoir::count_water_row).sum()
+    }
+
+    fn count_settled_water_row(row: &[Square]) -> usize {
+        row.iter().map(|x| if *x == Square::Still {
+                1
+            } else {
+                0
+            })
+            .sum()
+    }
+
+    fn count_settled_water(&self) -> usize {
+        self.grid.rows().map(Reservoir::count_settled_water_row).sum()
+    }
+
+
+}
+
+pub struct Answer {
+    // Squares reached by water, flowing or not.
+    pub water: usize,
+    // Squares with water that stays put.
+    pub settled_water: usize,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.water, self.settled_water)
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let lines = scan::parse_lines(input, Line::new)?;
+    if lines.is_empty() {
+        return Err(scan::unexpected_end(input, "a line of clay").into());
+    }
+
+    let extents = get_extents(&lines);
+    let (min_x, max_x, _, _) = extents;
+    // The water has to hit the clay, and there has to be room to
+    // flow off the sides of it.
+    if START_X < min_x || max_x < START_X {
+        return Err(Error::invalid("The spring must be above the clay"));
+    }
+
+    let mut reservoir = Reservoir::new(extents);
+    for line in lines.iter() {
+        reservoir.add(line);
+    }
+    let min_y = reservoir.grid.min_y();
+    reservoir.pour(START_X as isize, min_y);
+    Ok(Answer {
+        water: reservoir.count_water(),
+        settled_water: reservoir.count_settled_water(),
+    })
+}
@@ -0,0 +1,82 @@
+extern crate aoc_common;
+
+use aoc_common::{Grid, Result};
+
+fn _print_grid(grid: &Grid<char>) {
+    println!("{}", grid.render(|c| *c));
+}
+
+fn cell_step(grid: &Grid<char>, x: isize, y: isize) -> char {
+    let neighbour_cells: Vec<char> = grid
+        .neighbours8(x, y)
+        .map(|pos| grid[pos])
+        .collect::<Vec<char>>();
+    match grid[(x, y)] {
+        '.' => {
+            if neighbour_cells.iter().filter(|x| **x == '|').count() >= 3 {
+                '|'
+            } else {
+                '.'
+            }
+        }
+        '|' => {
+            if neighbour_cells.iter().filter(|x| **x == '#').count() >= 3 {
+                '#'
+            } else {
+                '|'
+            }
+        }
+        '#' => {
+            if neighbour_cells.iter().filter(|x| **x == '#').count() >= 1
+                && neighbour_cells.iter().filter(|x| **x == '|').count() >= 1
+            {
+                '#'
+            } else {
+                '.'
+            }
+        }
+        c => panic!("Unexpected character: {}", c),
+    }
+}
+
+// One timestep. We could do clever implementations with
+// iterators and stuff, but... let's just keep it simple.
+fn step(grid: &Grid<char>) -> Grid<char> {
+    let mut new_grid = grid.clone();
+    for (x, y) in grid.positions() {
+        new_grid[(x, y)] = cell_step(grid, x, y);
+    }
+    new_grid
+}
+
+fn score(grid: &Grid<char>) -> usize {
+    let mut woods = 0;
+    let mut lumberyards = 0;
+    for c in grid.cells() {
+        match c {
+            '|' => woods += 1,
+            '#' => lumberyards += 1,
+            _ => {}
+        }
+    }
+    woods * lumberyards
+}
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    // Cheesily, we'll keep in character domain. Every acre must be
+    // open ('.'), trees ('|') or a lumberyard ('#').
+    let mut grid = Grid::parse(input, "'.', '|' or '#'", |c| {
+        if ".|#".contains(c) {
+            Some(c)
+        } else {
+            None
+        }
+    })?;
+
+    for _ in 0..10 {
+        grid = step(&grid);
+    }
+    Ok(score(&grid))
+}
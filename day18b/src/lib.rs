@@ -0,0 +1,103 @@
+extern crate aoc_common;
+
+use aoc_common::{Grid, Result};
+
+const TARGET_CYCLES: u32 = 1000000000;
+const SKIP_CYCLES: u32 = 1000;
+
+fn _print_grid(grid: &Grid<char>) {
+    println!("{}", grid.render(|c| *c));
+}
+
+fn cell_step(grid: &Grid<char>, x: isize, y: isize) -> char {
+    let neighbour_cells: Vec<char> = grid
+        .neighbours8(x, y)
+        .map(|pos| grid[pos])
+        .collect::<Vec<char>>();
+    match grid[(x, y)] {
+        '.' => {
+            if neighbour_cells.iter().filter(|x| **x == '|').count() >= 3 {
+                '|'
+            } else {
+                '.'
+            }
+        }
+        '|' => {
+            if neighbour_cells.iter().filter(|x| **x == '#').count() >= 3 {
+                '#'
+            } else {
+                '|'
+            }
+        }
+        '#' => {
+            if neighbour_cells.iter().filter(|x| **x == '#').count() >= 1
+                && neighbour_cells.iter().filter(|x| **x == '|').count() >= 1
+            {
+                '#'
+            } else {
+                '.'
+            }
+        }
+        c => panic!("Unexpected character: {}", c),
+    }
+}
+
+// One timestep. We could do clever implementations with
+// iterators and stuff, but... let's just keep it simple.
+fn step(grid: &Grid<char>) -> Grid<char> {
+    let mut new_grid = grid.clone();
+    for (x, y) in grid.positions() {
+        new_grid[(x, y)] = cell_step(grid, x, y);
+    }
+    new_grid
+}
+
+fn score(grid: &Grid<char>) -> usize {
+    let mut woods = 0;
+    let mut lumberyards = 0;
+    for c in grid.cells() {
+        match c {
+            '|' => woods += 1,
+            '#' => lumberyards += 1,
+            _ => {}
+        }
+    }
+    woods * lumberyards
+}
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    // Cheesily, we'll keep in character domain. Every acre must be
+    // open ('.'), trees ('|') or a lumberyard ('#').
+    let mut grid = Grid::parse(input, "'.', '|' or '#'", |c| {
+        if ".|#".contains(c) {
+            Some(c)
+        } else {
+            None
+        }
+    })?;
+
+    // Step over initial steps to when we're pretty sure we've
+    // converged (easier than a rho algorithm).
+    for _ in 0..SKIP_CYCLES {
+        grid = step(&grid);
+    }
+
+    // Then find the number of steps to cycle...
+    let base_grid = grid.clone();
+    let mut cycle_length = 0;
+    loop {
+        grid = step(&grid);
+        cycle_length += 1;
+        if grid == base_grid {
+            break;
+        }
+    }
+    let steps = (TARGET_CYCLES - SKIP_CYCLES) % cycle_length;
+    for _ in 0..steps {
+        grid = step(&grid);
+    }
+
+    Ok(score(&grid))
+}
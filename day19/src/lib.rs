@@ -0,0 +1,43 @@
+extern crate aoc_common;
+extern crate elfcode;
+
+use aoc_common::{Error, Result};
+use elfcode::{Machine, Optimiser, Program};
+
+const NUM_REGS: usize = 6;
+
+pub struct Answer {
+    // Part a: Register 0 at the end, starting from all zeros.
+    pub first: usize,
+    // Part b: The same, but with register 0 starting at 1.
+    pub second: usize,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}\n{}", self.first, self.second)
+    }
+}
+
+// Run the program with register 0 set to "r0". The program sums
+// divisors the slow way, which would take forever for part b, so the
+// loops are replaced with closed-form versions.
+fn run(program: &Program, r0: usize) -> usize {
+    let optimiser = Optimiser::new(program);
+    let mut machine = Machine::new(program);
+    machine.regs_mut()[0] = r0;
+    optimiser.run(&mut machine);
+    machine.regs()[0]
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let program = Program::parse(input, NUM_REGS)?;
+    if program.ip().is_none() {
+        return Err(Error::invalid("The program must start with an \"#ip\" line"));
+    }
+
+    Ok(Answer {
+        first: run(&program, 0),
+        second: run(&program, 1),
+    })
+}
@@ -0,0 +1,33 @@
+extern crate aoc_common;
+extern crate elfcode;
+
+use aoc_common::{Error, Result};
+use elfcode::halting;
+use elfcode::Program;
+
+const NUM_REGS: usize = 6;
+
+pub struct Answer {
+    // Part a: The first value compared against, so fewest instructions.
+    pub first: usize,
+    // Part b: The last new value before it cycles, so most instructions.
+    pub last: usize,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}\n{}", self.first, self.last)
+    }
+}
+
+// The program halts when register 0 matches a value it computes, so
+// watch what that value is each time round.
+pub fn solve(input: &str) -> Result<Answer> {
+    let program = Program::parse(input, NUM_REGS)?;
+    let pc = halting::find_check(&program, 0).ok_or_else(|| Error::invalid("Register 0 is never compared"))?;
+    let values = halting::halting_values(&program, pc, 0).map_err(Error::invalid)?;
+    Ok(Answer {
+        first: values.first,
+        last: values.last,
+    })
+}
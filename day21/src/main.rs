@@ -1,26 +1,17 @@
-use std::collections::HashSet;
+extern crate day21;
 
-fn main() {
-    let mut seen = HashSet::new();
-
-    let mut r4: i64 = 0;
-    let mut r1: i64 = 0;
-    loop {
-        r1 = r4 | 0x010000;
-        r4 = 0xF49DE8;
+use std::io;
+use std::io::Read;
+use std::process;
 
-        while r1 > 0 {
-            r4 += r1 & 0xff;
-            r4 &= 0xffffff;
-            r4 *= 0x01016B;
-            r4 &= 0xffffff;
-            r1 = r1 >> 8;
-        }
-
-        if seen.contains(&r4) {
-            break;
+fn main() {
+    let mut buffer = String::new();
+    io::stdin().read_to_string(&mut buffer).expect("Read error");
+    match day21::solve(&buffer) {
+        Ok(answer) => println!("{}", answer),
+        Err(e) => {
+            eprintln!("{}", e.with_file("<stdin>"));
+            process::exit(1);
         }
-        println!("{}\n", r4);
-        seen.insert(r4);
     }
 }
@@ -0,0 +1,73 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{Grid, ParseResult, Result};
+
+fn get_erosion_levels(max_x: usize, max_y: usize, depth:usize) -> Grid<usize> {
+    let mut erosion_levels = Grid::new(max_x + 1, max_y + 1, 0);
+
+    // Filled in reading order, so the squares above and to the left
+    // are already done.
+    for (x, y) in erosion_levels.positions() {
+        let geologic_index = if y == 0 {
+            x as usize * 16807
+        } else if x == 0 {
+            y as usize * 48271
+        } else {
+            erosion_levels[(x - 1, y)] * erosion_levels[(x, y - 1)]
+        };
+        erosion_levels[(x, y)] = (geologic_index + depth) % 20183;
+    }
+
+    // Override target
+    erosion_levels[(max_x as isize, max_y as isize)] = depth % 20183;
+
+    erosion_levels
+}
+
+fn _print_erosion_levels(erosion_levels: &Grid<usize>) {
+    println!("{}", erosion_levels.render(|cell| match *cell % 3 {
+        0 => '.',
+        1 => '=',
+        _ => '|',
+    }));
+}
+
+fn sum_erosion_levels(erosion_levels: &Grid<usize>) -> usize {
+    erosion_levels.cells().map(|x| x % 3).sum()
+}
+
+// Input is "depth: D" then "target: X,Y".
+fn parse(input: &str) -> ParseResult<(usize, usize, usize)> {
+    let mut lines = scan::lines(input).filter(|s| !s.rest().trim().is_empty());
+
+    let mut s = lines
+        .next()
+        .ok_or_else(|| scan::unexpected_end(input, "\"depth:\""))?;
+    s.literal("depth:")?;
+    let depth = s.number()?;
+    s.end()?;
+
+    let mut s = lines
+        .next()
+        .ok_or_else(|| scan::unexpected_end(input, "\"target:\""))?;
+    s.literal("target:")?;
+    let x = s.number()?;
+    s.literal(",")?;
+    let y = s.number()?;
+    s.end()?;
+
+    if let Some(mut s) = lines.next() {
+        s.skip_whitespace();
+        return Err(s.error("end of input"));
+    }
+    Ok((x, y, depth))
+}
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let (tgt_x, tgt_y, depth) = parse(input)?;
+    let erosion_levels = get_erosion_levels(tgt_x, tgt_y, depth);
+    Ok(sum_erosion_levels(&erosion_levels))
+}
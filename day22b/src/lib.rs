@@ -0,0 +1,145 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{Grid, ParseResult, Result};
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+// It's can be a very roundabout route there... some kind of A* or
+// meet-in-the-middle would be better, but this is good enough.
+const SLACK: usize = 1000;
+
+// Step to build the map of erosion levels
+fn get_erosion_levels(tgt_x: usize, tgt_y: usize, depth:usize) -> Grid<usize> {
+    let max_x = tgt_x + SLACK;
+    let max_y = tgt_y + SLACK;
+    let target = (tgt_x as isize, tgt_y as isize);
+    let mut erosion_levels = Grid::new(max_x + 1, max_y + 1, 0);
+
+    // Filled in reading order, so the squares above and to the left
+    // are already done.
+    for (x, y) in erosion_levels.positions() {
+        let geologic_index = if y == 0 {
+            x as usize * 16807
+        } else if x == 0 {
+            y as usize * 48271
+        } else if (x, y) == target {
+            0
+        } else {
+            erosion_levels[(x - 1, y)] * erosion_levels[(x, y - 1)]
+        };
+        erosion_levels[(x, y)] = (geologic_index + depth) % 20183;
+    }
+
+    erosion_levels
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+enum Equipment {
+    ClimingGear,
+    Torch,
+    Neither
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+struct State {
+    x: isize,
+    y: isize,
+    e: Equipment
+}
+
+// Then perform a breadth-first search of the site
+fn time_to_target(start: State, end: State, erosion_levels: &Grid<usize>) -> usize {
+    // Set of States we've found the optimal route for.
+    let mut seen: HashSet<State> = HashSet::new();
+    // Time ordered queue of places to go to.
+    let mut queue = HashMap::new();
+    // Current time-step.
+    let mut time = 0;
+
+    // Initialise the starting position...
+    queue.insert(0, vec![start]);
+
+    loop {
+        // println!("Time {}", time);
+        if let Some(states) = queue.remove(&time) {
+            for s in states.into_iter() {
+                if s == end {
+                    return time;
+                }
+                if !seen.insert(s) {
+                    continue;
+                }
+                // println!("Seen: {:?} at {}", s, time);
+                {
+                    let move_time = queue.entry(time + 1).or_insert(Vec::new());
+                    add_if_safe(erosion_levels, move_time, State{ x: s.x - 1, y: s.y, e: s.e });
+                    add_if_safe(erosion_levels, move_time, State{ x: s.x + 1, y: s.y, e: s.e });
+                    add_if_safe(erosion_levels, move_time, State{ x: s.x, y: s.y - 1, e: s.e });
+                    add_if_safe(erosion_levels, move_time, State{ x: s.x, y: s.y + 1, e: s.e });
+                }
+                {
+                    let switch_time = queue.entry(time + 7).or_insert(Vec::new());
+                    add_if_safe(erosion_levels, switch_time, State{ x: s.x, y: s.y, e: Equipment::ClimingGear });
+                    add_if_safe(erosion_levels, switch_time, State{ x: s.x, y: s.y, e: Equipment::Torch });
+                    add_if_safe(erosion_levels, switch_time, State{ x: s.x, y: s.y, e: Equipment::Neither });
+                }
+            }
+        }
+        time += 1;
+    }
+}
+
+// Add the new state only if it's a safe one.
+fn add_if_safe(erosion_levels: &Grid<usize>, queue: &mut Vec<State>, s: State) {
+    let region_type = match erosion_levels.get(s.x, s.y) {
+        Some(level) => level % 3,
+        None => return,
+    };
+
+    match (s.e, region_type) {
+        (Equipment::ClimingGear, 2) => return,
+        (Equipment::Torch, 1) => return,
+        (Equipment::Neither, 0) => return,
+        _ => (),
+    }
+
+    queue.push(s);
+}
+
+// Input is "depth: D" then "target: X,Y".
+fn parse(input: &str) -> ParseResult<(usize, usize, usize)> {
+    let mut lines = scan::lines(input).filter(|s| !s.rest().trim().is_empty());
+
+    let mut s = lines
+        .next()
+        .ok_or_else(|| scan::unexpected_end(input, "\"depth:\""))?;
+    s.literal("depth:")?;
+    let depth = s.number()?;
+    s.end()?;
+
+    let mut s = lines
+        .next()
+        .ok_or_else(|| scan::unexpected_end(input, "\"target:\""))?;
+    s.literal("target:")?;
+    let x = s.number()?;
+    s.literal(",")?;
+    let y = s.number()?;
+    s.end()?;
+
+    if let Some(mut s) = lines.next() {
+        s.skip_whitespace();
+        return Err(s.error("end of input"));
+    }
+    Ok((x, y, depth))
+}
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let (tgt_x, tgt_y, depth) = parse(input)?;
+    let erosion_levels = get_erosion_levels(tgt_x, tgt_y, depth);
+    let start = State { x: 0, y: 0, e: Equipment::Torch };
+    let end = State { x: tgt_x as isize, y: tgt_y as isize, e: Equipment::Torch };
+    Ok(time_to_target(start, end, &erosion_levels))
+}
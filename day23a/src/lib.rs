@@ -0,0 +1,46 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result, Scanner};
+
+// Representation of rectangle with lower bound included, upper bound
+// excluded.
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+struct Nanobot {
+    r: i64,
+    x: i64,
+    y: i64,
+    z: i64
+}
+
+impl Nanobot {
+    fn in_range_of(&self, n: &Nanobot) -> bool {
+        (n.x - self.x).abs() + (n.y - self.y).abs() + (n.z - self.z).abs() <= self.r
+    }
+}
+
+// Lines look like "pos=<0,0,0>, r=4".
+fn read_nanobot(s: &mut Scanner) -> ParseResult<Nanobot> {
+    s.literal("pos=<")?;
+    let x = s.number()?;
+    s.literal(",")?;
+    let y = s.number()?;
+    s.literal(",")?;
+    let z = s.number()?;
+    s.literal(">, r=")?;
+    let r = s.number()?;
+    Ok(Nanobot { r, x, y, z })
+}
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let nanobots = scan::parse_lines(input, read_nanobot)?;
+    if nanobots.is_empty() {
+        return Err(scan::unexpected_end(input, "a nanobot").into());
+    }
+
+    let strongest = nanobots.iter().max().unwrap();
+
+    Ok(nanobots.iter().filter(|x| strongest.in_range_of(x)).count())
+}
@@ -0,0 +1,197 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result, Scanner};
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+#[derive(Debug, PartialEq)]
+enum Side {
+    Immune,
+    Infection,
+}
+
+#[derive(Debug)]
+struct Group {
+    id: usize,
+    side: Side,
+    unit_count: u64,
+    hit_points: u64,
+    immune_modifiers: HashSet<String>,
+    weak_modifiers: HashSet<String>,
+    attack_damage: u64,
+    attack_type: String,
+    initiative: u64,
+}
+
+impl Group {
+    fn attack_multiplier(self: &Group, target: &Group) -> u64 {
+        let t = &self.attack_type;
+        if target.immune_modifiers.contains(t) {
+            0
+        } else if target.weak_modifiers.contains(t) {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+// Modifiers look like "weak fire immune slashing cold", running up to
+// the next comma.
+fn read_immune_weak(s: &mut Scanner) -> ParseResult<(HashSet<String>, HashSet<String>)> {
+    let mut immunes = HashSet::new();
+    let mut weaks = HashSet::new();
+
+    let mut current = 0;
+    loop {
+        s.skip_whitespace();
+        if s.peek() == Some(',') {
+            break;
+        }
+        let column = s.column();
+        match s.word()? {
+            "weak" => current = 1,
+            "immune" => current = 2,
+            part => {
+                if current == 1 {
+                    weaks.insert(part.to_string());
+                } else if current == 2 {
+                    immunes.insert(part.to_string());
+                } else {
+                    return Err(s.error_at(column, "\"weak\" or \"immune\"", part));
+                }
+            }
+        }
+    }
+
+    Ok((immunes, weaks))
+}
+
+// Groups are in the hand-expanded input format, one per line:
+//
+//   Immune,504,1697,weak fire immune slashing,28,fire,4
+//
+// for side, units, hit points, modifiers, damage, attack type and
+// initiative.
+fn read_group(s: &mut Scanner, id: usize) -> ParseResult<Group> {
+    let side = match s.keyword(&["Immune", "Infection"])? {
+        "Immune" => Side::Immune,
+        _ => Side::Infection,
+    };
+    s.literal(",")?;
+    let unit_count = s.number()?;
+    s.literal(",")?;
+    let hit_points = s.number()?;
+    s.literal(",")?;
+    let (immunes, weaks) = read_immune_weak(s)?;
+    s.literal(",")?;
+    let attack_damage = s.number()?;
+    s.literal(",")?;
+    let attack_type = s.word()?.to_string();
+    s.literal(",")?;
+    let initiative = s.number()?;
+    Ok(Group {
+        id,
+        side,
+        unit_count,
+        hit_points,
+        immune_modifiers: immunes,
+        weak_modifiers: weaks,
+        attack_damage,
+        attack_type,
+        initiative,
+    })
+}
+
+// Return a number based on effective power, then initiative (assumes initiative < 100), to sort by.
+fn power_rank(group: &&Group) -> i64 {
+    0 - (group.unit_count * group.attack_damage * 100 + group.initiative) as i64
+}
+
+fn select_targets(groups: &[Group]) -> HashMap<usize, usize> {
+    // Sort groups by effective power
+    let mut group_refs = groups.iter().collect::<Vec<&Group>>();
+    group_refs.sort_by_key(power_rank);
+
+    // Targets that have been chosen already, and can't be re-selected.
+    let mut chosen = HashSet::new();
+    // Mapping from attacker id to target id (if there is one).
+    let mut mapping = HashMap::new();
+
+    for attacker in group_refs.iter() {
+        if attacker.unit_count == 0 {
+            // Dead groups don't attack.
+            continue;
+        }
+
+        // Use rev so that we get the highest effective power in a
+        // draw (max_by_key returns the last of equals)
+        let opt_target = group_refs.iter()
+            .rev()
+            .filter(|target| target.side != attacker.side && target.unit_count != 0 && !chosen.contains(&target.id))
+            .max_by_key(|target| attacker.attack_multiplier(target));
+        if let Some(target) = opt_target {
+            // Hang on, if the best we can do is nothing, don't bother.
+            if attacker.attack_multiplier(target) == 0 {
+                continue;
+            }
+
+            let target_id = target.id;
+            chosen.insert(target_id);
+            mapping.insert(attacker.id, target_id);
+        }
+    }
+    mapping
+}
+
+fn perform_attacks(groups: &mut [Group], targets: &HashMap<usize, usize>) {
+    // Sort groups by initiative.
+    let mut attacker_ids = (0..groups.len()).collect::<Vec<usize>>();
+    attacker_ids.sort_by_key(|group_id| -(groups[*group_id].initiative as i64));
+
+    for attacker_id in attacker_ids.iter() {
+        // To work around the borrow-checker, we will work in two
+        // phases: Decide the attack to make, then apply it.
+        let (target_id, damage) = {
+            let attacker = &groups[*attacker_id];
+            // Dead groups don't attack.
+            if attacker.unit_count == 0 {
+                continue;
+            }
+            // Only do something if we have a target.
+            if let Some(target_id) = targets.get(&attacker.id) {
+                let damage = attacker.unit_count * attacker.attack_damage * attacker.attack_multiplier(&groups[*target_id]);
+                // println!("{} would attack {} for {}", attacker.id, target_id, damage);
+                (target_id, damage)
+            } else {
+                continue;
+            }
+        };
+
+        let target = &mut groups[*target_id];
+        let kill_count = damage / target.hit_points;
+        // println!("{} attacked for {}, killing {}", target_id, damage, kill_count);
+        target.unit_count -= kill_count.min(target.unit_count);
+    }
+}
+
+pub type Answer = u64;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut id = 0;
+    let mut groups = scan::parse_lines(input, |s| {
+        id += 1;
+        read_group(s, id - 1)
+    })?;
+
+    loop {
+        let targets = select_targets(&groups);
+        if targets.is_empty() {
+            break;
+        }
+        perform_attacks(&mut groups, &targets);
+    }
+
+    Ok(groups.iter().map(|group| group.unit_count).sum::<u64>())
+}
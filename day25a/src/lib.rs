@@ -0,0 +1,104 @@
+extern crate aoc_common;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Result, Scanner};
+use std::collections::HashMap;
+
+const CONSTELLATION_BOUND: i32 = 3;
+
+#[derive(Debug)]
+struct Point {
+    a: i32,
+    b: i32,
+    c: i32,
+    d: i32,
+}
+
+impl Point {
+    fn dist(self: &Point, other: &Point) -> i32 {
+        (self.a - other.a).abs()
+        + (self.b - other.b).abs()
+        + (self.c - other.c).abs()
+        + (self.d - other.d).abs()
+
+    }
+}
+
+// Points are four comma-separated numbers, like "-1,2,2,0".
+fn read_point(s: &mut Scanner) -> ParseResult<Point> {
+    let a = s.number()?;
+    s.literal(",")?;
+    let b = s.number()?;
+    s.literal(",")?;
+    let c = s.number()?;
+    s.literal(",")?;
+    let d = s.number()?;
+    Ok(Point { a, b, c, d })
+}
+
+struct UnionFind {
+    u: HashMap<usize, Option<usize>>,
+}
+
+impl UnionFind {
+    fn new() -> UnionFind {
+        UnionFind {
+            u: HashMap::new(),
+        }
+    }
+
+    fn insert(self: &mut UnionFind, i: usize) {
+        if self.u.insert(i, None).is_some() {
+            panic!("Set {} already inserted", i);
+        }
+    }
+
+    // Union the sets and return the current id of the unioned sets.
+    fn union(self: &mut UnionFind, i: usize, j: usize) -> usize{
+        if let Some(parent_i) = *self.u.get(&i).unwrap() {
+            let res = self.union(parent_i, j);
+            self.u.insert(i, Some(res));
+            res
+        } else if let Some(parent_j) = *self.u.get(&j).unwrap() {
+            let res = self.union(i, parent_j);
+            self.u.insert(j, Some(res));
+            res
+        } else {
+            // Both have no parent, union 'em.
+            if i != j {
+                self.u.insert(j, Some(i));
+            }
+            i
+        }
+    }
+
+    // Count parent-less things. This will be the number of distinct sets.
+    fn count(self: &UnionFind) -> usize {
+        self.u.values().filter(|x| x.is_none()).count()
+    }
+}
+
+pub type Answer = usize;
+
+pub fn solve(input: &str) -> Result<Answer> {
+    let points = scan::parse_lines(input, read_point)?;
+
+    // Build a list of pairs of points in the same constellation.
+    let mut edges = Vec::new();
+    for i in 0..points.len() {
+        for j in (i+1)..points.len() {
+            if points[i].dist(&points[j]) <= CONSTELLATION_BOUND {
+                edges.push((i, j));
+            }
+        }
+    }
+
+    let mut uf = UnionFind::new();
+    for i in 0..points.len() {
+        uf.insert(i);
+    }
+    for (i, j) in edges.iter() {
+        uf.union(*i, *j);
+    }
+    Ok(uf.count())
+}
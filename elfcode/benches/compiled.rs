@@ -0,0 +1,59 @@
+// Time the compiled code against the interpreter on days 19 and 21,
+// failing if it's not 10 times faster. Run with "cargo bench".
+
+extern crate elfcode;
+
+use elfcode::{bench, halting, Machine, Program};
+use std::fs;
+use std::path::Path;
+use std::process;
+
+const MIN_SPEEDUP: f64 = 10.0;
+
+// What to put in register 0 to make a program stop.
+type Reg0 = fn(&Program) -> usize;
+
+fn zero(_: &Program) -> usize {
+    0
+}
+
+// The register 0 that day 21 takes longest to stop for, which is its
+// part b, found the same way. Part a's is over too soon to time.
+fn slowest_halt(program: &Program) -> usize {
+    let pc = halting::find_check(program, 0).expect("Register 0 is never compared");
+    halting::halting_values(program, pc, 0).unwrap().last
+}
+
+// Each program with its register 0, and how many times to run it,
+// keeping the best. Day 19 is over quickly enough to be thrown by noise.
+const PROGRAMS: &[(&str, Reg0, usize)] =
+    &[("day19/input/input.txt", zero, 10), ("day21/input/input.txt", slowest_halt, 1)];
+
+fn main() {
+    let mut slow = false;
+    for &(path, reg0, runs) in PROGRAMS.iter() {
+        let text = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(path)).unwrap();
+        let program = Program::parse(&text, 6).unwrap();
+        let mut start = Machine::new(&program);
+        start.regs_mut()[0] = reg0(&program);
+        let timing = (0..runs)
+            .map(|_| bench::time(&start).unwrap_or_else(|e| panic!("{}: {}", path, e)))
+            .max_by(|a, b| a.speedup().partial_cmp(&b.speedup()).unwrap())
+            .unwrap();
+        println!(
+            "{}: {} steps, interpreter {:.3?}, compiled {:.3?}, {:.1}x faster",
+            path,
+            timing.steps,
+            timing.interpreted,
+            timing.compiled,
+            timing.speedup()
+        );
+        if timing.speedup() < MIN_SPEEDUP {
+            eprintln!("{}: compiled code is less than {}x faster", path, MIN_SPEEDUP);
+            slow = true;
+        }
+    }
+    if slow {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,393 @@
+// An assembler for annotated ElfCode listings, and a disassembler that
+// produces them. On top of the plain program format, a listing may
+// have:
+//
+//   ; comments, to the end of the line
+//   top:          labels, alone or in front of an instruction
+//   07            addresses, which are checked against the real ones
+//   pc            in place of the register bound by "#ip"
+//   seti top 0 pc jumps to labels
+//
+// As the instruction pointer is incremented after every instruction,
+// a label used as an operand of an instruction that writes to "pc"
+// stands for the address just before it, so that execution continues
+// at the label. Anywhere else it's just the label's address.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use aoc_common::scan;
+use aoc_common::{ParseError, ParseResult, Scanner};
+use opcode::Opcode;
+use program::{Instruction, Program};
+
+#[derive(Debug, Clone, Copy)]
+enum Operand<'a> {
+    Num(usize),
+    Pc,
+    Label(&'a str),
+}
+
+struct Line<'a> {
+    line: usize,
+    opcode: Opcode,
+    // Column of each operand, for errors.
+    operands: [(Operand<'a>, usize); 3],
+}
+
+fn is_label_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn read_operand<'a>(s: &mut Scanner<'a>) -> ParseResult<(Operand<'a>, usize)> {
+    s.skip_whitespace();
+    let column = s.column();
+    let operand = match s.peek() {
+        Some(c) if c.is_ascii_digit() => Operand::Num(s.number()?),
+        Some(c) if is_label_start(c) => match s.word()? {
+            "pc" => Operand::Pc,
+            name => Operand::Label(name),
+        },
+        _ => return Err(s.error("an operand")),
+    };
+    Ok((operand, column))
+}
+
+// If the scanner is at "name:", read it and return the name.
+fn read_label<'a>(s: &mut Scanner<'a>) -> Option<&'a str> {
+    let mut ahead = s.clone();
+    ahead.skip_whitespace();
+    if !ahead.peek().is_some_and(is_label_start) {
+        return None;
+    }
+    let name = ahead.word().ok()?;
+    if ahead.next_char() != Some(':') {
+        return None;
+    }
+    *s = ahead;
+    Some(name)
+}
+
+// The "#ip" line, if this is one.
+fn read_ip(s: &mut Scanner, num_regs: usize) -> ParseResult<Option<usize>> {
+    s.skip_whitespace();
+    if s.peek() != Some('#') {
+        return Ok(None);
+    }
+    s.literal("#ip")?;
+    s.skip_whitespace();
+    let column = s.column();
+    let reg: usize = s.number()?;
+    if reg >= num_regs {
+        let expected = format!("a register number (0-{})", num_regs - 1);
+        return Err(s.error_at(column, &expected, &reg.to_string()));
+    }
+    s.end()?;
+    Ok(Some(reg))
+}
+
+fn resolve(
+    operand: Operand,
+    column: usize,
+    line: &Line,
+    is_reg: bool,
+    num_regs: usize,
+    ip: Option<usize>,
+    labels: &HashMap<&str, usize>,
+) -> ParseResult<usize> {
+    let error = |expected: &str, found: &str| ParseError::new(line.line, column, expected, &format!("{:?}", found));
+    let reg_expected = || format!("a register number (0-{})", num_regs - 1);
+    match (operand, is_reg) {
+        (Operand::Num(n), true) if n >= num_regs => Err(error(&reg_expected(), &n.to_string())),
+        (Operand::Num(n), _) => Ok(n),
+        (Operand::Pc, true) => ip.ok_or_else(|| error("a register (\"pc\" needs an \"#ip\" line)", "pc")),
+        (Operand::Pc, false) => Err(error("a value", "pc")),
+        (Operand::Label(name), true) => Err(error(&reg_expected(), name)),
+        (Operand::Label(name), false) => {
+            let addr = *labels.get(name).ok_or_else(|| error("a defined label", name))?;
+            let writes_pc = match line.operands[2].0 {
+                Operand::Pc => true,
+                Operand::Num(c) => Some(c) == ip,
+                Operand::Label(_) => false,
+            };
+            if !writes_pc {
+                Ok(addr)
+            } else if addr > 0 {
+                Ok(addr - 1)
+            } else {
+                Err(error("a label after the first instruction to jump to", name))
+            }
+        }
+    }
+}
+
+// Turn an annotated listing into a program, with register operands
+// less than "num_regs".
+pub fn assemble(input: &str, num_regs: usize) -> ParseResult<Program> {
+    let mut ip = None;
+    let mut labels = HashMap::new();
+    let mut lines = Vec::new();
+
+    for (text, line) in input.lines().zip(1..) {
+        let text = match text.find(';') {
+            Some(pos) => &text[..pos],
+            None => text,
+        };
+        let mut s = Scanner::new(text, line);
+
+        if lines.is_empty() && labels.is_empty() && ip.is_none() {
+            ip = read_ip(&mut s, num_regs)?;
+            if ip.is_some() {
+                continue;
+            }
+        }
+
+        s.skip_whitespace();
+        if s.peek().is_some_and(|c| c.is_ascii_digit()) {
+            let column = s.column();
+            let addr: usize = s.number()?;
+            if addr != lines.len() {
+                let expected = format!("address {}", lines.len());
+                return Err(s.error_at(column, &expected, &addr.to_string()));
+            }
+        }
+
+        loop {
+            let column = {
+                s.skip_whitespace();
+                s.column()
+            };
+            match read_label(&mut s) {
+                Some(name) => {
+                    if labels.insert(name, lines.len()).is_some() {
+                        return Err(s.error_at(column, "a new label", name));
+                    }
+                }
+                None => break,
+            }
+        }
+
+        s.skip_whitespace();
+        if s.at_end() {
+            continue;
+        }
+        let column = s.column();
+        let name = s.word()?;
+        let opcode = Opcode::from_name(name).ok_or_else(|| s.error_at(column, "an opcode", name))?;
+        let operands = [read_operand(&mut s)?, read_operand(&mut s)?, read_operand(&mut s)?];
+        s.end()?;
+        lines.push(Line { line, opcode, operands });
+    }
+
+    let instrs = lines
+        .iter()
+        .map(|line| {
+            let (a_reg, b_reg) = line.opcode.reg_operands();
+            let mut vals = [0; 3];
+            for (i, is_reg) in [a_reg, b_reg, true].iter().enumerate() {
+                let (operand, column) = line.operands[i];
+                vals[i] = resolve(operand, column, line, *is_reg, num_regs, ip, &labels)?;
+            }
+            Ok(Instruction::new(line.opcode, vals[0], vals[1], vals[2]))
+        }).collect::<ParseResult<Vec<_>>>()?;
+
+    if instrs.is_empty() {
+        return Err(scan::unexpected_end(input, "an instruction"));
+    }
+    Ok(Program::new(num_regs, ip, instrs))
+}
+
+// How an instruction changes the instruction pointer, if it does.
+enum Jump {
+    // Always goes to the given address.
+    To(usize),
+    // Skips the next instruction if the register is 1.
+    SkipIf(usize),
+}
+
+fn jump(program: &Program, pc: usize) -> Option<Jump> {
+    let ip = program.ip()?;
+    let instrs = program.instrs();
+    let i = &instrs[pc];
+    if i.c != ip {
+        return None;
+    }
+    match i.opcode {
+        Opcode::Seti => i.a.checked_add(1).map(Jump::To),
+        Opcode::Addi if i.a == ip => i.b.checked_add(pc + 1).map(Jump::To),
+        // Only treat "addr rX pc pc" as a skip if rX was just set by a
+        // comparison, so it's 0 or 1.
+        Opcode::Addr if (i.a == ip) != (i.b == ip) => {
+            let reg = if i.a == ip { i.b } else { i.a };
+            let prev = instrs.get(pc.wrapping_sub(1))?;
+            if prev.opcode.is_comparison() && prev.c == reg {
+                Some(Jump::SkipIf(reg))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn reg_name(program: &Program, reg: usize) -> String {
+    if Some(reg) == program.ip() {
+        "pc".to_string()
+    } else {
+        format!("r{}", reg)
+    }
+}
+
+// A one-line description of what an instruction does, for the
+// listing's comments.
+fn describe(program: &Program, instr: &Instruction) -> String {
+    let (a_reg, b_reg) = instr.opcode.reg_operands();
+    let operand = |val, is_reg| if is_reg { reg_name(program, val) } else { val.to_string() };
+    let (a, b, c) = (operand(instr.a, a_reg), operand(instr.b, b_reg), reg_name(program, instr.c));
+    let op = match instr.opcode {
+        Opcode::Addr | Opcode::Addi => "+",
+        Opcode::Mulr | Opcode::Muli => "*",
+        Opcode::Banr | Opcode::Bani => "&",
+        Opcode::Borr | Opcode::Bori => "|",
+        Opcode::Setr | Opcode::Seti => return format!("{} = {}", c, a),
+        Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr => return format!("{} = {} > {}", c, a, b),
+        Opcode::Eqir | Opcode::Eqri | Opcode::Eqrr => return format!("{} = {} == {}", c, a, b),
+    };
+    // The register-register forms are all commutative.
+    if a == c {
+        format!("{} {}= {}", c, op, b)
+    } else if b == c && b_reg {
+        format!("{} {}= {}", c, op, a)
+    } else {
+        format!("{} = {} {} {}", c, a, op, b)
+    }
+}
+
+// Produce an annotated listing of the program, which assembles back
+// to the same program. Jump targets get labels, and each instruction
+// a comment saying what it does.
+pub fn disassemble(program: &Program) -> String {
+    let len = program.len();
+    let jumps = (0..len).map(|pc| jump(program, pc)).collect::<Vec<_>>();
+
+    let mut labels = BTreeMap::new();
+    for (pc, j) in jumps.iter().enumerate() {
+        let target = match j {
+            Some(Jump::To(target)) => *target,
+            Some(Jump::SkipIf(_)) => pc + 2,
+            None => continue,
+        };
+        if target <= len {
+            labels.insert(target, String::new());
+        }
+    }
+    for (n, name) in labels.values_mut().enumerate() {
+        *name = format!("L{}", n);
+    }
+
+    let mut res = String::new();
+    if let Some(ip) = program.ip() {
+        writeln!(res, "#ip {}", ip).unwrap();
+    }
+    for (pc, instr) in program.instrs().iter().enumerate() {
+        let label = match labels.get(&pc) {
+            Some(name) => format!("{}:", name),
+            None => String::new(),
+        };
+        let (a_reg, b_reg) = instr.opcode.reg_operands();
+        let operand = |val, is_reg| {
+            if is_reg && Some(val) == program.ip() {
+                "pc".to_string()
+            } else {
+                val.to_string()
+            }
+        };
+        let mut a = operand(instr.a, a_reg);
+        let comment = match jumps[pc] {
+            Some(Jump::To(target)) => match labels.get(&target) {
+                Some(name) => {
+                    if instr.opcode == Opcode::Seti {
+                        a = name.clone();
+                    }
+                    format!("jmp {}", name)
+                }
+                None => format!("jmp {}", target),
+            },
+            Some(Jump::SkipIf(reg)) => match labels.get(&(pc + 2)) {
+                Some(name) => format!("if r{} jmp {}", reg, name),
+                None => format!("if r{} jmp {}", reg, pc + 2),
+            },
+            None => describe(program, instr),
+        };
+        let text = format!("{} {} {} {}", instr.opcode, a, operand(instr.b, b_reg), operand(instr.c, true));
+        writeln!(res, "{:<8}{:<24}; {}", label, text, comment).unwrap();
+    }
+    if let Some(name) = labels.get(&len) {
+        writeln!(res, "{}:", name).unwrap();
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "#ip 2
+; Count r1 up to 3.
+00        seti 0 0 1      ; r1 = 0
+01  top:  addi 1 1 1
+          eqri 1 3 3
+          addr 3 pc pc    ; if r3 jmp end
+          seti top 0 pc
+end:
+";
+
+    #[test]
+    fn assembles_listing() {
+        let program = assemble(LISTING, 6).unwrap();
+        let plain = Program::parse("#ip 2\nseti 0 0 1\naddi 1 1 1\neqri 1 3 3\naddr 3 2 2\nseti 0 0 2\n", 6).unwrap();
+        assert_eq!(program, plain);
+    }
+
+    #[test]
+    fn assembly_errors() {
+        let e = assemble("seti nowhere 0 1\n", 6).unwrap_err();
+        assert_eq!((e.line, e.column, e.expected.as_str()), (1, 6, "a defined label"));
+        let e = assemble("a: seti 0 0 1\na: seti 0 0 1\n", 6).unwrap_err();
+        assert_eq!((e.line, e.expected.as_str()), (2, "a new label"));
+        let e = assemble("00 seti 0 0 1\n02 seti 0 0 1\n", 6).unwrap_err();
+        assert_eq!((e.line, e.expected.as_str(), e.found.as_str()), (2, "address 1", "\"2\""));
+        let e = assemble("seti 0 0 pc\n", 6).unwrap_err();
+        assert_eq!(e.expected, "a register (\"pc\" needs an \"#ip\" line)");
+        let e = assemble("#ip 0\ntop: seti top 0 pc\n", 6).unwrap_err();
+        assert_eq!(e.expected, "a label after the first instruction to jump to");
+    }
+
+    #[test]
+    fn disassembles() {
+        let program = assemble(LISTING, 6).unwrap();
+        let listing = disassemble(&program);
+        assert_eq!(
+            listing,
+            "#ip 2
+        seti 0 0 1              ; r1 = 0
+L0:     addi 1 1 1              ; r1 += 1
+        eqri 1 3 3              ; r3 = r1 == 3
+        addr 3 pc pc            ; if r3 jmp L1
+        seti L0 0 pc            ; jmp L0
+L1:
+"
+        );
+        assert_eq!(assemble(&listing, 6).unwrap(), program);
+
+        // A skip off the end of the program has nowhere to put a label.
+        let program = assemble("#ip 0\neqri 1 3 3\naddr 3 0 0\n", 6).unwrap();
+        assert_eq!(
+            disassemble(&program),
+            "#ip 0
+        eqri 1 3 3              ; r3 = r1 == 3
+        addr 3 pc pc            ; if r3 jmp 3
+"
+        );
+    }
+}
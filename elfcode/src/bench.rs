@@ -0,0 +1,58 @@
+// Time the compiled code against the interpreter, checking they agree.
+
+use compile::Compiled;
+use machine::Machine;
+use std::time::{Duration, Instant};
+
+pub struct Timing {
+    pub steps: u64,
+    // The registers at the end.
+    pub regs: Vec<usize>,
+    pub interpreted: Duration,
+    // Including compiling.
+    pub compiled: Duration,
+    pub compiling: Duration,
+}
+
+impl Timing {
+    // How many times faster the compiled code was.
+    pub fn speedup(&self) -> f64 {
+        self.interpreted.as_secs_f64() / self.compiled.as_secs_f64()
+    }
+
+    pub fn per_step(&self, time: Duration) -> f64 {
+        time.as_secs_f64() * 1e9 / self.steps.max(1) as f64
+    }
+}
+
+// Run from "start" both ways.
+pub fn time(start: &Machine) -> Result<Timing, String> {
+    let mut plain = start.clone();
+    let time = Instant::now();
+    plain.run();
+    let interpreted = time.elapsed();
+
+    let mut fast = start.clone();
+    let time = Instant::now();
+    let compiled = Compiled::new(start.program());
+    let compiling = time.elapsed();
+    compiled.run(&mut fast);
+    let compiled = time.elapsed();
+
+    if fast.regs() != plain.regs() || fast.steps() != plain.steps() {
+        return Err(format!(
+            "Compiled code disagrees: {:?} after {} steps, not {:?} after {}",
+            fast.regs(),
+            fast.steps(),
+            plain.regs(),
+            plain.steps()
+        ));
+    }
+    Ok(Timing {
+        steps: plain.steps(),
+        regs: plain.regs().to_vec(),
+        interpreted,
+        compiled,
+        compiling,
+    })
+}
@@ -0,0 +1,588 @@
+// Compile a program to closures, to run it without decoding each
+// instruction as it goes.
+//
+// Each instruction becomes a closure specialised to its opcode and
+// operands, which calls the closure for the next one. Starting from
+// each instruction, the closures follow the program as far as they
+// can: jumps to constant addresses are followed at compile time, and a
+// comparison followed by a conditional skip becomes a branch between
+// two closures, which also runs the instruction before it if that's a
+// plain one. They stop at anything that loops back on itself, or
+// jumps somewhere only known at run time, and return where to go next,
+// so the instruction pointer is only looked at between those, and go
+// round any loop back to where they started. Reads of
+// the instruction pointer register are compiled in as constants, and
+// it's only written back when the program stops, which leaves it as
+// the interpreter would have.
+
+use machine::Machine;
+use opcode::Opcode;
+use program::Program;
+
+// Runs from an instruction, counting the instructions run, and
+// returns the next one to run.
+type Code = Box<dyn Fn(&mut Regs, &mut u64) -> usize>;
+
+// Where to go after an instruction: on to more code, or back to the
+// top level at the given instruction, having run "n" instructions.
+// Returning is done in place, rather than by calling code that does
+// it, as it happens every time round a loop.
+enum Next {
+    Code(Code),
+    Return(usize, u64),
+}
+
+impl Next {
+    #[inline(always)]
+    fn run(&self, regs: &mut Regs, steps: &mut u64) -> usize {
+        match *self {
+            Next::Code(ref code) => code(regs, steps),
+            Next::Return(pc, n) => {
+                *steps += n;
+                pc
+            }
+        }
+    }
+
+    fn into_code(self) -> Code {
+        match self {
+            Next::Code(code) => code,
+            Next::Return(pc, n) => Box::new(move |_, steps| {
+                *steps += n;
+                pc
+            }),
+        }
+    }
+}
+
+// The registers, in an array big enough for any program that's
+// compiled, so that reading a register needn't check it's there.
+const MAX_REGS: usize = 8;
+type Regs = [usize; MAX_REGS];
+
+// How far to follow the program from a starting point, in instructions
+// and branches.
+const MAX_LEN: usize = 64;
+const MAX_BRANCHES: usize = 6;
+
+#[derive(Clone, Copy)]
+enum Src {
+    Reg(usize),
+    Imm(usize),
+}
+
+// Evaluate "body" with "name" bound to the register number "r". The
+// low register numbers that programs actually use are made constants,
+// so that the closures built from them go straight to the register,
+// which is much faster than looking the number up. It's only done for
+// the first operand and the destination, as doing it for every operand
+// makes for so many closures that the crate takes over a minute to build.
+macro_rules! with_reg {
+    ($r:expr, |$name:ident| $body:expr) => {
+        match $r {
+            0 => {
+                const $name: usize = 0;
+                $body
+            }
+            1 => {
+                const $name: usize = 1;
+                $body
+            }
+            2 => {
+                const $name: usize = 2;
+                $body
+            }
+            3 => {
+                const $name: usize = 3;
+                $body
+            }
+            4 => {
+                const $name: usize = 4;
+                $body
+            }
+            5 => {
+                const $name: usize = 5;
+                $body
+            }
+            r => {
+                #[allow(non_snake_case)]
+                let $name = r % MAX_REGS;
+                $body
+            }
+        }
+    };
+}
+
+// Build a closure that reads the operands into "x" and "y", and then
+// evaluates "finish" with the result in "v". With "loop", it goes round
+// again while "finish" gives "start", rather than returning to go round
+// from the top level.
+macro_rules! with_operands {
+    ($a:expr, $b:expr, |$x:ident, $y:ident| $val:expr, |$r:ident, $s:ident, $v:ident| $finish:expr) => {
+        match ($a, $b) {
+            (Src::Reg(a), Src::Reg(b)) => with_reg!(a, |A| {
+                Box::new(move |$r: &mut Regs, $s: &mut u64| {
+                    let ($x, $y) = ($r[A], $r[b % MAX_REGS]);
+                    let $v = $val;
+                    $finish
+                }) as Code
+            }),
+            (Src::Reg(a), Src::Imm(b)) => with_reg!(a, |A| {
+                Box::new(move |$r: &mut Regs, $s: &mut u64| {
+                    let ($x, $y) = ($r[A], b);
+                    let $v = $val;
+                    $finish
+                }) as Code
+            }),
+            (Src::Imm(a), Src::Reg(b)) => Box::new(move |$r: &mut Regs, $s: &mut u64| {
+                let ($x, $y) = (a, $r[b % MAX_REGS]);
+                let $v = $val;
+                $finish
+            }),
+            (Src::Imm(a), Src::Imm(b)) => Box::new(move |$r: &mut Regs, $s: &mut u64| {
+                let ($x, $y) = (a, b);
+                let $v = $val;
+                $finish
+            }),
+        }
+    };
+    (
+        $a:expr,
+        $b:expr,
+        loop $start:expr,
+        |$x:ident, $y:ident| $val:expr,
+        |$r:ident, $s:ident, $v:ident| $finish:expr
+    ) => {{
+        let start = $start;
+        match ($a, $b) {
+            (Src::Reg(a), Src::Reg(b)) => with_reg!(a, |A| {
+                Box::new(move |$r: &mut Regs, $s: &mut u64| loop {
+                    let ($x, $y) = ($r[A], $r[b % MAX_REGS]);
+                    let $v = $val;
+                    let pc = $finish;
+                    if pc != start {
+                        return pc;
+                    }
+                }) as Code
+            }),
+            (Src::Reg(a), Src::Imm(b)) => with_reg!(a, |A| {
+                Box::new(move |$r: &mut Regs, $s: &mut u64| loop {
+                    let ($x, $y) = ($r[A], b);
+                    let $v = $val;
+                    let pc = $finish;
+                    if pc != start {
+                        return pc;
+                    }
+                }) as Code
+            }),
+            (Src::Imm(a), Src::Reg(b)) => Box::new(move |$r: &mut Regs, $s: &mut u64| loop {
+                let ($x, $y) = (a, $r[b % MAX_REGS]);
+                let $v = $val;
+                let pc = $finish;
+                if pc != start {
+                    return pc;
+                }
+            }),
+            (Src::Imm(a), Src::Imm(b)) => Box::new(move |$r: &mut Regs, $s: &mut u64| loop {
+                let ($x, $y) = (a, b);
+                let $v = $val;
+                let pc = $finish;
+                if pc != start {
+                    return pc;
+                }
+            }),
+        }
+    }};
+}
+
+// The same, specialised to the opcode.
+macro_rules! compile_op {
+    ($opcode:expr, $a:expr, $b:expr, |$r:ident, $s:ident, $v:ident| $finish:expr) => {
+        match $opcode {
+            Opcode::Addr | Opcode::Addi => {
+                with_operands!($a, $b, |x, y| x.wrapping_add(y), |$r, $s, $v| $finish)
+            }
+            Opcode::Mulr | Opcode::Muli => {
+                with_operands!($a, $b, |x, y| x.wrapping_mul(y), |$r, $s, $v| $finish)
+            }
+            Opcode::Banr | Opcode::Bani => with_operands!($a, $b, |x, y| x & y, |$r, $s, $v| $finish),
+            Opcode::Borr | Opcode::Bori => with_operands!($a, $b, |x, y| x | y, |$r, $s, $v| $finish),
+            Opcode::Setr | Opcode::Seti => with_operands!($a, $b, |x, _y| x, |$r, $s, $v| $finish),
+            Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr => {
+                with_operands!($a, $b, |x, y| (x > y) as usize, |$r, $s, $v| $finish)
+            }
+            Opcode::Eqir | Opcode::Eqri | Opcode::Eqrr => {
+                with_operands!($a, $b, |x, y| (x == y) as usize, |$r, $s, $v| $finish)
+            }
+        }
+    };
+    ($opcode:expr, $a:expr, $b:expr, loop $start:expr, |$r:ident, $s:ident, $v:ident| $finish:expr) => {
+        match $opcode {
+            Opcode::Addr | Opcode::Addi => {
+                with_operands!($a, $b, loop $start, |x, y| x.wrapping_add(y), |$r, $s, $v| $finish)
+            }
+            Opcode::Mulr | Opcode::Muli => {
+                with_operands!($a, $b, loop $start, |x, y| x.wrapping_mul(y), |$r, $s, $v| $finish)
+            }
+            Opcode::Banr | Opcode::Bani => with_operands!($a, $b, loop $start, |x, y| x & y, |$r, $s, $v| $finish),
+            Opcode::Borr | Opcode::Bori => with_operands!($a, $b, loop $start, |x, y| x | y, |$r, $s, $v| $finish),
+            Opcode::Setr | Opcode::Seti => with_operands!($a, $b, loop $start, |x, _y| x, |$r, $s, $v| $finish),
+            Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr => {
+                with_operands!($a, $b, loop $start, |x, y| (x > y) as usize, |$r, $s, $v| $finish)
+            }
+            Opcode::Eqir | Opcode::Eqri | Opcode::Eqrr => {
+                with_operands!($a, $b, loop $start, |x, y| (x == y) as usize, |$r, $s, $v| $finish)
+            }
+        }
+    };
+}
+
+// Evaluate "body" with "run" bound to a closure that runs an instruction
+// that doesn't jump, for running inside the closure of the branch after
+// it, to save a call. Its registers aren't made constants, as that
+// would make too many closures.
+macro_rules! with_plain_operands {
+    ($a:expr, $b:expr, $c:expr, |$x:ident, $y:ident| $val:expr, |$run:ident| $body:expr) => {{
+        let c = $c % MAX_REGS;
+        match ($a, $b) {
+            (Src::Reg(a), Src::Reg(b)) => {
+                let $run = move |r: &mut Regs| {
+                    let ($x, $y) = (r[a % MAX_REGS], r[b % MAX_REGS]);
+                    r[c] = $val;
+                };
+                $body
+            }
+            (Src::Reg(a), Src::Imm(b)) => {
+                let $run = move |r: &mut Regs| {
+                    let ($x, $y) = (r[a % MAX_REGS], b);
+                    r[c] = $val;
+                };
+                $body
+            }
+            (Src::Imm(a), Src::Reg(b)) => {
+                let $run = move |r: &mut Regs| {
+                    let ($x, $y) = (a, r[b % MAX_REGS]);
+                    r[c] = $val;
+                };
+                $body
+            }
+            (Src::Imm(a), Src::Imm(b)) => {
+                let $run = move |r: &mut Regs| {
+                    let ($x, $y) = (a, b);
+                    r[c] = $val;
+                };
+                $body
+            }
+        }
+    }};
+}
+
+// The same, specialised to the opcode.
+macro_rules! with_plain_op {
+    ($opcode:expr, $a:expr, $b:expr, $c:expr, |$run:ident| $body:expr) => {
+        match $opcode {
+            Opcode::Addr | Opcode::Addi => {
+                with_plain_operands!($a, $b, $c, |x, y| x.wrapping_add(y), |$run| $body)
+            }
+            Opcode::Mulr | Opcode::Muli => {
+                with_plain_operands!($a, $b, $c, |x, y| x.wrapping_mul(y), |$run| $body)
+            }
+            Opcode::Banr | Opcode::Bani => with_plain_operands!($a, $b, $c, |x, y| x & y, |$run| $body),
+            Opcode::Borr | Opcode::Bori => with_plain_operands!($a, $b, $c, |x, y| x | y, |$run| $body),
+            Opcode::Setr | Opcode::Seti => with_plain_operands!($a, $b, $c, |x, _y| x, |$run| $body),
+            Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr => {
+                with_plain_operands!($a, $b, $c, |x, y| (x > y) as usize, |$run| $body)
+            }
+            Opcode::Eqir | Opcode::Eqri | Opcode::Eqrr => {
+                with_plain_operands!($a, $b, $c, |x, y| (x == y) as usize, |$run| $body)
+            }
+        }
+    };
+}
+
+// A branch on the comparison "cmp" of "a" and "b", which is stored in
+// register "c", after running "before".
+fn branch_after<F, G>(before: F, cmp: G, a: Src, b: Src, c: usize, skip: Next, no_skip: Next) -> Code
+where
+    F: Fn(&mut Regs) + 'static,
+    G: Fn(usize, usize) -> bool + 'static,
+{
+    let c = c % MAX_REGS;
+    let branch = move |r: &mut Regs, s: &mut u64, v: bool| {
+        r[c] = v as usize;
+        if v {
+            skip.run(r, s)
+        } else {
+            no_skip.run(r, s)
+        }
+    };
+    match (a, b) {
+        (Src::Reg(a), Src::Reg(b)) => Box::new(move |r: &mut Regs, s: &mut u64| {
+            before(r);
+            let v = cmp(r[a % MAX_REGS], r[b % MAX_REGS]);
+            branch(r, s, v)
+        }),
+        (Src::Reg(a), Src::Imm(b)) => Box::new(move |r: &mut Regs, s: &mut u64| {
+            before(r);
+            let v = cmp(r[a % MAX_REGS], b);
+            branch(r, s, v)
+        }),
+        (Src::Imm(a), Src::Reg(b)) => Box::new(move |r: &mut Regs, s: &mut u64| {
+            before(r);
+            let v = cmp(a, r[b % MAX_REGS]);
+            branch(r, s, v)
+        }),
+        (Src::Imm(a), Src::Imm(b)) => Box::new(move |r: &mut Regs, s: &mut u64| {
+            before(r);
+            let v = cmp(a, b);
+            branch(r, s, v)
+        }),
+    }
+}
+
+pub struct Compiled {
+    ip: Option<usize>,
+    // Indexed by the instruction to start from. None if the program
+    // has too many registers, and is left to the interpreter.
+    code: Option<Vec<Code>>,
+}
+
+impl Compiled {
+    pub fn new(program: &Program) -> Compiled {
+        if program.num_regs() > MAX_REGS {
+            return Compiled {
+                ip: program.ip(),
+                code: None,
+            };
+        }
+        let builder = Builder { program };
+        let code = (0..program.len())
+            .map(|start| {
+                let body = builder.build(start, &mut Vec::new(), 0).into_code();
+                // Go round loops here, rather than back through run.
+                Box::new(move |regs: &mut Regs, steps: &mut u64| loop {
+                    let pc = body(regs, steps);
+                    if pc != start {
+                        return pc;
+                    }
+                }) as Code
+            })
+            .collect();
+        Compiled {
+            ip: program.ip(),
+            code: Some(code),
+        }
+    }
+
+    // Run the machine until it halts.
+    pub fn run(&self, machine: &mut Machine) {
+        let code = match self.code {
+            Some(ref code) => code,
+            None => return machine.run(),
+        };
+        assert_eq!(code.len(), machine.program().len());
+        let mut regs = [0; MAX_REGS];
+        let num_regs = machine.regs().len();
+        regs[..num_regs].copy_from_slice(machine.regs());
+        let mut pc = machine.pc();
+        let mut steps = 0;
+        while let Some(code) = code.get(pc) {
+            pc = code(&mut regs, &mut steps);
+        }
+        // The last instruction left the register pointing at itself,
+        // or wherever it jumped to.
+        if let Some(ip) = self.ip {
+            if steps > 0 {
+                regs[ip] = pc.wrapping_sub(1);
+            }
+        }
+        machine.regs_mut().copy_from_slice(&regs[..num_regs]);
+        machine.advance(pc, steps);
+    }
+}
+
+struct Builder<'a> {
+    program: &'a Program,
+}
+
+impl<'a> Builder<'a> {
+    fn is_ip(&self, r: usize) -> bool {
+        Some(r) == self.program.ip()
+    }
+
+    fn operands(&self, pc: usize) -> (Src, Src) {
+        let instr = &self.program.instrs()[pc];
+        let (a_reg, b_reg) = instr.opcode.reg_operands();
+        let src = |val, is_reg| match is_reg {
+            true if self.is_ip(val) => Src::Imm(pc),
+            true => Src::Reg(val),
+            false => Src::Imm(val),
+        };
+        let b = match instr.opcode {
+            Opcode::Setr | Opcode::Seti => Src::Imm(0),
+            _ => src(instr.b, b_reg),
+        };
+        (src(instr.a, a_reg), b)
+    }
+
+    // Whether the instruction at "pc" skips the next one if register
+    // "r" is set, as in "addr r pc pc".
+    fn is_skip(&self, pc: usize, r: usize) -> bool {
+        match self.program.instrs().get(pc) {
+            Some(instr) => {
+                instr.opcode == Opcode::Addr
+                    && self.is_ip(instr.c)
+                    && !self.is_ip(r)
+                    && ((instr.a == r && self.is_ip(instr.b)) || (self.is_ip(instr.a) && instr.b == r))
+            }
+            None => false,
+        }
+    }
+
+    // Whether the instruction at "pc" is a comparison followed by a
+    // skip on its result, which can be made into a branch.
+    fn is_branch(&self, pc: usize, branches: usize) -> bool {
+        match self.program.instrs().get(pc) {
+            Some(instr) => instr.opcode.is_comparison() && branches < MAX_BRANCHES && self.is_skip(pc + 1, instr.c),
+            None => false,
+        }
+    }
+
+    // Code to run from "pc", having got there by running the
+    // instructions in "path" since the last return to the top level.
+    fn build(&self, pc: usize, path: &mut Vec<usize>, branches: usize) -> Next {
+        let n = path.len() as u64;
+        if pc >= self.program.len() || path.contains(&pc) || path.len() >= MAX_LEN {
+            return Next::Return(pc, n);
+        }
+
+        let instr = self.program.instrs()[pc];
+        let (a, b) = self.operands(pc);
+        path.push(pc);
+        let code = if self.is_ip(instr.c) {
+            match (a, b) {
+                // A jump to a known place, so just carry on from there.
+                (Src::Imm(x), Src::Imm(y)) => {
+                    // Put the values where apply will look for them.
+                    let (a_reg, b_reg) = instr.opcode.reg_operands();
+                    let a = if a_reg { 0 } else { x };
+                    let b = if b_reg { 1 } else { y };
+                    let target = instr.opcode.apply(&[x, y], a, b);
+                    self.build(target.wrapping_add(1), path, branches)
+                }
+                _ => Next::Code(compile_op!(instr.opcode, a, b, |_r, s, v| {
+                    *s += n + 1;
+                    v.wrapping_add(1)
+                })),
+            }
+        } else if self.is_branch(pc + 1, branches) && !path.contains(&(pc + 1)) && path.len() + 2 < MAX_LEN {
+            // Run this in the closure for the branch after it.
+            let next = self.program.instrs()[pc + 1];
+            let (next_a, next_b) = self.operands(pc + 1);
+            path.extend(&[pc + 1, pc + 2]);
+            let skip = self.build(pc + 4, path, branches + 1);
+            let no_skip = self.build(pc + 3, path, branches + 1);
+            path.truncate(path.len() - 2);
+            Next::Code(with_plain_op!(instr.opcode, a, b, instr.c, |before| match next.opcode {
+                Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr => {
+                    branch_after(before, |x, y| x > y, next_a, next_b, next.c, skip, no_skip)
+                }
+                _ => branch_after(before, |x, y| x == y, next_a, next_b, next.c, skip, no_skip),
+            }))
+        } else if self.is_branch(pc, branches) {
+            path.push(pc + 1);
+            let skip = self.build(pc + 3, path, branches + 1);
+            let no_skip = self.build(pc + 2, path, branches + 1);
+            path.pop();
+            Next::Code(with_reg!(instr.c, |C| {
+                compile_op!(instr.opcode, a, b, |r, s, v| {
+                    r[C] = v;
+                    if v != 0 {
+                        skip.run(r, s)
+                    } else {
+                        no_skip.run(r, s)
+                    }
+                })
+            }))
+        } else {
+            let next = self.build(pc + 1, path, branches);
+            Next::Code(with_reg!(instr.c, |C| {
+                // Small loops often start with one of these, so the first
+                // goes round them itself, which saves a call each time.
+                if path.len() == 1 {
+                    compile_op!(instr.opcode, a, b, loop pc, |r, s, v| {
+                        r[C] = v;
+                        next.run(r, s)
+                    })
+                } else {
+                    compile_op!(instr.opcode, a, b, |r, s, v| {
+                        r[C] = v;
+                        next.run(r, s)
+                    })
+                }
+            }))
+        };
+        path.pop();
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use program::Instruction;
+
+    // Run from the given registers both ways, and compare.
+    fn check(program: &Program, regs: &[usize]) {
+        let compiled = Compiled::new(program);
+        let mut plain = Machine::new(program);
+        plain.regs_mut().copy_from_slice(regs);
+        let mut fast = plain.clone();
+        plain.run();
+        compiled.run(&mut fast);
+        assert_eq!(fast.regs(), plain.regs(), "{} from {:?}", program, regs);
+        assert_eq!(fast.pc(), plain.pc(), "{} from {:?}", program, regs);
+        assert_eq!(fast.steps(), plain.steps(), "{} from {:?}", program, regs);
+    }
+
+    #[test]
+    fn every_opcode() {
+        let regs = [3, 100, 7, 0, 12, 0];
+        for opcode in Opcode::ALL.iter() {
+            let (a_reg, b_reg) = opcode.reg_operands();
+            let operands = |is_reg| if is_reg { vec![0, 1, 4, 5] } else { vec![0, 2, 200] };
+            for a in operands(a_reg) {
+                for b in operands(b_reg) {
+                    // Register 5 is the instruction pointer.
+                    for c in [2, 5].iter() {
+                        let instrs = vec![Instruction::new(*opcode, a, b, *c), Instruction::new(Opcode::Addi, 2, 1, 2)];
+                        check(&Program::new(6, Some(5), instrs.clone()), &regs);
+                        check(&Program::new(6, None, instrs), &regs);
+                        // And run with the branch after it.
+                        let instrs = vec![
+                            Instruction::new(*opcode, a, b, *c),
+                            Instruction::new(Opcode::Gtri, 2, 50, 3),
+                            Instruction::new(Opcode::Addr, 3, 5, 5),
+                            Instruction::new(Opcode::Addi, 2, 1, 2),
+                        ];
+                        check(&Program::new(6, Some(5), instrs), &regs);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn loops() {
+        // Day 19's example, and its divisor sum on small numbers.
+        let example = "#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\naddr 1 2 3\nsetr 1 0 0\nseti 8 0 4\nseti 9 0 5\n";
+        check(&Program::parse(example, 6).unwrap(), &[0; 6]);
+        let divisors = "#ip 2\nseti 1 0 1\nseti 1 3 3\nmulr 1 3 5\neqrr 5 4 5\naddr 5 2 2\naddi 2 1 2\n\
+                        addr 1 0 0\naddi 3 1 3\ngtrr 3 4 5\naddr 2 5 2\nseti 1 6 2\naddi 1 1 1\ngtrr 1 4 5\n\
+                        addr 5 2 2\nseti 0 1 2\n";
+        let program = Program::parse(divisors, 6).unwrap();
+        for n in 0..20 {
+            check(&program, &[0, 0, 0, 0, n, 0]);
+        }
+    }
+}
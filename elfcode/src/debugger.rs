@@ -0,0 +1,322 @@
+// An interactive debugger for ElfCode programs. It's driven one
+// command line at a time, so that the REPL in main.rs stays trivial
+// and the commands can be tested without a terminal.
+
+use std::fmt::Write as FmtWrite;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use machine::Machine;
+use program::Program;
+
+pub const HELP: &str = "Commands:
+    step [n], s       Run one (or n) instructions
+    continue, c       Run until a breakpoint, watchpoint or halt
+    break <pc>, b     Stop before running the instruction at pc
+    delete <pc>       Remove a breakpoint
+    watch <reg>, w    Stop after an instruction writes to the register
+    unwatch <reg>     Remove a watchpoint
+    regs, r           Show the instruction pointer and registers
+    set <reg> <val>   Change a register
+    list, l           Show the program, with breakpoints and position
+    hist              Show how many times each instruction has run
+    trace <file>      Write each instruction executed to the file
+    trace off         Stop tracing
+    reset             Go back to the start, keeping breakpoints
+    quit, q           Leave";
+
+pub struct Debugger<'a> {
+    machine: Machine<'a>,
+    // Indexed by instruction.
+    breakpoints: Vec<bool>,
+    // Indexed by register.
+    watchpoints: Vec<bool>,
+    // Times each instruction has been executed.
+    histogram: Vec<u64>,
+    trace: Option<BufWriter<File>>,
+}
+
+// Why execution stopped.
+enum Stop {
+    Breakpoint,
+    // Register, old value, new value, and the instruction that did it.
+    Write(usize, usize, usize, usize),
+    Halted,
+    // Ran the requested number of steps.
+    Done,
+    TraceError(String),
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a Program) -> Debugger<'a> {
+        Debugger {
+            machine: Machine::new(program),
+            breakpoints: vec![false; program.len()],
+            watchpoints: vec![false; program.num_regs()],
+            histogram: vec![0; program.len()],
+            trace: None,
+        }
+    }
+
+    pub fn machine(&self) -> &Machine<'a> {
+        &self.machine
+    }
+
+    // Run a single command, returning what to print, or None to quit.
+    pub fn command(&mut self, line: &str) -> Option<String> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let (cmd, args) = match words.split_first() {
+            Some((cmd, args)) => (*cmd, args),
+            None => return Some(String::new()),
+        };
+        let res = match cmd {
+            "step" | "s" => self.cmd_step(args),
+            "continue" | "c" => Ok(self.run(None)),
+            "break" | "b" => self.set_breakpoint(args, true),
+            "delete" => self.set_breakpoint(args, false),
+            "watch" | "w" => self.set_watchpoint(args, true),
+            "unwatch" => self.set_watchpoint(args, false),
+            "regs" | "r" => Ok(self.show_regs()),
+            "set" => self.cmd_set(args),
+            "list" | "l" => Ok(self.list()),
+            "hist" => Ok(self.show_histogram()),
+            "trace" => self.cmd_trace(args),
+            "reset" => Ok(self.reset()),
+            "help" | "h" => Ok(HELP.to_string()),
+            "quit" | "q" => return None,
+            _ => Err(format!("Unknown command: {} (try \"help\")", cmd)),
+        };
+        Some(res.unwrap_or_else(|e| e))
+    }
+
+    fn cmd_step(&mut self, args: &[&str]) -> Result<String, String> {
+        let n = match args {
+            [] => 1,
+            [n] => number(n)?,
+            _ => return Err("Usage: step [n]".to_string()),
+        };
+        Ok(self.run(Some(n)))
+    }
+
+    fn set_breakpoint(&mut self, args: &[&str], on: bool) -> Result<String, String> {
+        let pc = match args {
+            [pc] => number(pc)?,
+            _ => return Err("Need an instruction number".to_string()),
+        };
+        match self.breakpoints.get_mut(pc) {
+            Some(b) => *b = on,
+            None => return Err(format!("No instruction {}", pc)),
+        }
+        Ok(String::new())
+    }
+
+    fn set_watchpoint(&mut self, args: &[&str], on: bool) -> Result<String, String> {
+        let reg = match args {
+            [reg] => self.register(reg)?,
+            _ => return Err("Need a register number".to_string()),
+        };
+        self.watchpoints[reg] = on;
+        Ok(String::new())
+    }
+
+    fn cmd_set(&mut self, args: &[&str]) -> Result<String, String> {
+        let (reg, val) = match args {
+            [reg, val] => (self.register(reg)?, number(val)?),
+            _ => return Err("Usage: set <reg> <value>".to_string()),
+        };
+        self.machine.regs_mut()[reg] = val;
+        Ok(self.show_regs())
+    }
+
+    fn cmd_trace(&mut self, args: &[&str]) -> Result<String, String> {
+        match args {
+            ["off"] => {
+                self.stop_trace()?;
+                Ok(String::new())
+            }
+            [path] => {
+                self.stop_trace()?;
+                let file = File::create(path).map_err(|e| format!("Couldn't create {}: {}", path, e))?;
+                self.trace = Some(BufWriter::new(file));
+                Ok(format!("Tracing to {}", path))
+            }
+            _ => Err("Usage: trace <file> | trace off".to_string()),
+        }
+    }
+
+    fn stop_trace(&mut self) -> Result<(), String> {
+        match self.trace.take() {
+            Some(mut t) => t.flush().map_err(|e| format!("Couldn't write trace: {}", e)),
+            None => Ok(()),
+        }
+    }
+
+    fn register(&self, s: &str) -> Result<usize, String> {
+        let reg = number(s)?;
+        if reg >= self.watchpoints.len() {
+            return Err(format!("No register {}", reg));
+        }
+        Ok(reg)
+    }
+
+    fn reset(&mut self) -> String {
+        self.machine = Machine::new(self.machine.program());
+        self.histogram.iter_mut().for_each(|n| *n = 0);
+        self.show_regs()
+    }
+
+    // Execute one instruction, noting it in the histogram and trace.
+    fn step(&mut self) -> Result<(), String> {
+        let pc = self.machine.pc();
+        self.machine.step();
+        self.histogram[pc] += 1;
+        if let Some(ref mut t) = self.trace {
+            let instr = self.machine.program().instrs()[pc];
+            writeln!(t, "{:>4}  {:<20} {:?}", pc, instr.to_string(), self.machine.regs())
+                .map_err(|e| format!("Couldn't write trace: {}", e))?;
+        }
+        Ok(())
+    }
+
+    // Run for up to "limit" steps, or until something interesting
+    // happens. Breakpoints are ignored on the first step, so that
+    // continuing from one makes progress.
+    fn run(&mut self, limit: Option<usize>) -> String {
+        let mut count = 0;
+        let stop = loop {
+            if limit == Some(count) {
+                break Stop::Done;
+            }
+            let pc = self.machine.pc();
+            let instr = match self.machine.next_instr() {
+                Some(instr) => *instr,
+                None => break Stop::Halted,
+            };
+            if count > 0 && self.breakpoints[pc] {
+                break Stop::Breakpoint;
+            }
+            let old = self.machine.regs()[instr.c];
+            if let Err(e) = self.step() {
+                self.trace = None;
+                break Stop::TraceError(e);
+            }
+            count += 1;
+            if self.watchpoints[instr.c] {
+                break Stop::Write(instr.c, old, self.machine.regs()[instr.c], pc);
+            }
+        };
+
+        let mut res = String::new();
+        match stop {
+            Stop::Breakpoint => writeln!(res, "Breakpoint at {}", self.machine.pc()).unwrap(),
+            Stop::Write(reg, old, new, pc) => {
+                writeln!(res, "Register {} written by {}: {} -> {}", reg, pc, old, new).unwrap()
+            }
+            Stop::Halted => writeln!(res, "Halted after {} steps", self.machine.steps()).unwrap(),
+            Stop::Done => {}
+            Stop::TraceError(e) => writeln!(res, "{}", e).unwrap(),
+        }
+        res.push_str(&self.show_regs());
+        res
+    }
+
+    fn show_regs(&self) -> String {
+        let next = match self.machine.next_instr() {
+            Some(instr) => instr.to_string(),
+            None => "(halted)".to_string(),
+        };
+        format!("ip={} {:?}  {}", self.machine.pc(), self.machine.regs(), next)
+    }
+
+    fn list(&self) -> String {
+        let program = self.machine.program();
+        let mut res = String::new();
+        if let Some(ip) = program.ip() {
+            writeln!(res, "      #ip {}", ip).unwrap();
+        }
+        for (pc, instr) in program.instrs().iter().enumerate() {
+            let here = if pc == self.machine.pc() { '>' } else { ' ' };
+            let brk = if self.breakpoints[pc] { '*' } else { ' ' };
+            writeln!(res, "{}{}{:>4} {}", here, brk, pc, instr).unwrap();
+        }
+        res.pop();
+        res
+    }
+
+    fn show_histogram(&self) -> String {
+        let total = self.histogram.iter().sum::<u64>().max(1);
+        let program = self.machine.program();
+        let mut res = String::new();
+        for (pc, (count, instr)) in self.histogram.iter().zip(program.instrs()).enumerate() {
+            let percent = *count as f64 * 100.0 / total as f64;
+            writeln!(res, "{:>4} {:>12} {:>6.2}%  {}", pc, count, percent, instr).unwrap();
+        }
+        res.pop();
+        res
+    }
+}
+
+impl<'a> Drop for Debugger<'a> {
+    fn drop(&mut self) {
+        let _ = self.stop_trace();
+    }
+}
+
+fn number(s: &str) -> Result<usize, String> {
+    s.parse().map_err(|_| format!("Bad number: {}", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The day 19 example.
+    const EXAMPLE: &str = "#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5
+";
+
+    #[test]
+    fn breakpoints_and_stepping() {
+        let program = Program::parse(EXAMPLE, 6).unwrap();
+        let mut db = Debugger::new(&program);
+        assert_eq!(db.command("b 4"), Some(String::new()));
+        assert_eq!(db.command("c").unwrap(), "Breakpoint at 4\nip=4 [3, 5, 6, 0, 0, 0]  setr 1 0 0");
+        assert_eq!(db.command("s").unwrap(), "ip=6 [5, 5, 6, 0, 0, 0]  seti 9 0 5");
+        assert_eq!(db.command("c").unwrap(), "Halted after 5 steps\nip=7 [6, 5, 6, 0, 0, 9]  (halted)");
+        assert_eq!(db.command("q"), None);
+    }
+
+    #[test]
+    fn watchpoints() {
+        let program = Program::parse(EXAMPLE, 6).unwrap();
+        let mut db = Debugger::new(&program);
+        db.command("w 2");
+        assert_eq!(
+            db.command("c").unwrap(),
+            "Register 2 written by 1: 0 -> 6\nip=2 [1, 5, 6, 0, 0, 0]  addi 0 1 0"
+        );
+        db.command("unwatch 2");
+        db.command("set 3 1");
+        db.command("c");
+        assert_eq!(db.machine().regs(), &[6, 5, 6, 1, 0, 9]);
+    }
+
+    #[test]
+    fn histogram_and_errors() {
+        let program = Program::parse(EXAMPLE, 6).unwrap();
+        let mut db = Debugger::new(&program);
+        db.command("c");
+        let hist = db.command("hist").unwrap();
+        assert_eq!(hist.lines().nth(3).unwrap(), "   3            0   0.00%  addr 1 2 3");
+        assert_eq!(hist.lines().nth(6).unwrap(), "   6            1  20.00%  seti 9 0 5");
+        assert_eq!(db.command("b 7").unwrap(), "No instruction 7");
+        assert_eq!(db.command("w 6").unwrap(), "No register 6");
+        assert_eq!(db.command("frob").unwrap(), "Unknown command: frob (try \"help\")");
+    }
+}
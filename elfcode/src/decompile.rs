@@ -0,0 +1,756 @@
+// A decompiler from ElfCode to C-like pseudo-code.
+//
+// The program is split into basic blocks at the jumps, which are the
+// instructions that write to the register bound by "#ip". Loops are
+// found from back edges to a block that dominates the jump, and
+// conditional skips ("addr rX pc pc" after a comparison) become
+// if/else. Anything that doesn't fit falls back to labels and gotos.
+//
+// Jumps through a register that isn't known to be 0 or 1, like day
+// 19's "addr pc 0 pc", are printed as they are, but analysed as if
+// they skip at most one instruction, which is how the puzzle inputs
+// use them.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use opcode::Opcode;
+use program::{Instruction, Program};
+
+// A condition, and its negation.
+#[derive(Debug, Clone)]
+struct Cond {
+    text: String,
+    negated: String,
+}
+
+impl Cond {
+    fn not(&self) -> Cond {
+        Cond {
+            text: self.negated.clone(),
+            negated: self.text.clone(),
+        }
+    }
+}
+
+// How a block ends.
+#[derive(Debug, Clone)]
+enum Term {
+    // Carries on to the next instruction.
+    Fall,
+    // A jump, which halts if it's off the end of the program.
+    Goto(usize),
+    // Skips the next instruction if the register is set.
+    SkipIf(usize),
+    // A jump to somewhere computed at run time.
+    Computed(String),
+}
+
+#[derive(Debug)]
+struct Block {
+    start: usize,
+    // Exclusive.
+    end: usize,
+    term: Term,
+}
+
+#[derive(Debug)]
+enum Stmt {
+    Assign(String),
+    If(Cond, Vec<Stmt>, Vec<Stmt>),
+    Loop(Vec<Stmt>),
+    While(Cond, Vec<Stmt>),
+    DoWhile(Vec<Stmt>, Cond),
+    Break,
+    Continue,
+    Goto(usize),
+    Computed(String),
+    Halt,
+    // The start of a block, which gets a label if anything jumps to it.
+    Mark(usize),
+}
+
+impl Stmt {
+    // Whether control never carries on to the next statement.
+    fn is_jump(&self) -> bool {
+        matches!(
+            self,
+            Stmt::Break | Stmt::Continue | Stmt::Goto(_) | Stmt::Computed(_) | Stmt::Halt
+        )
+    }
+}
+
+// Where "break" and "continue" go, in the innermost loop.
+#[derive(Clone, Copy)]
+struct LoopCtx {
+    head: usize,
+    exit: usize,
+}
+
+struct Decompiler<'a> {
+    program: &'a Program,
+    blocks: Vec<Block>,
+    // Indexed by block: the registers live on entry.
+    live_in: Vec<Vec<bool>>,
+    // Indexed by block: the blocks that dominate it.
+    doms: Vec<Vec<bool>>,
+}
+
+fn reg(r: usize) -> String {
+    format!("r{}", r)
+}
+
+impl<'a> Decompiler<'a> {
+    fn new(program: &'a Program) -> Decompiler<'a> {
+        let mut d = Decompiler {
+            program,
+            blocks: Vec::new(),
+            live_in: Vec::new(),
+            doms: Vec::new(),
+        };
+        d.find_blocks();
+        d.find_liveness();
+        d.find_dominators();
+        d
+    }
+
+    fn len(&self) -> usize {
+        self.program.len()
+    }
+
+    fn is_ip(&self, r: usize) -> bool {
+        Some(r) == self.program.ip()
+    }
+
+    // An operand as an expression. Reading the instruction pointer
+    // gives the instruction's own address.
+    fn operand(&self, pc: usize, val: usize, is_reg: bool) -> String {
+        if !is_reg {
+            val.to_string()
+        } else if self.is_ip(val) {
+            pc.to_string()
+        } else {
+            reg(val)
+        }
+    }
+
+    // The value an instruction computes, if it doesn't depend on any
+    // register other than the instruction pointer.
+    fn constant(&self, pc: usize, instr: &Instruction) -> Option<usize> {
+        let (a_reg, b_reg) = instr.opcode.reg_operands();
+        let known = |val, is_reg: bool| !is_reg || self.is_ip(val);
+        let uses_b = !matches!(instr.opcode, Opcode::Setr | Opcode::Seti);
+        if !known(instr.a, a_reg) || (uses_b && !known(instr.b, b_reg)) {
+            return None;
+        }
+        let mut regs = vec![0; self.program.num_regs()];
+        if let Some(ip) = self.program.ip() {
+            regs[ip] = pc;
+        }
+        Some(instr.opcode.apply(&regs, instr.a, instr.b))
+    }
+
+    // The right hand side of an instruction.
+    fn expr(&self, pc: usize, instr: &Instruction) -> String {
+        let (a_reg, b_reg) = instr.opcode.reg_operands();
+        let a = self.operand(pc, instr.a, a_reg);
+        let b = self.operand(pc, instr.b, b_reg);
+        match instr.opcode {
+            Opcode::Setr | Opcode::Seti => a,
+            _ if instr.opcode.is_comparison() => self.comparison(pc, instr).text,
+            _ => format!("{} {} {}", a, op(instr.opcode), b),
+        }
+    }
+
+    fn comparison(&self, pc: usize, instr: &Instruction) -> Cond {
+        let (a_reg, b_reg) = instr.opcode.reg_operands();
+        let a = self.operand(pc, instr.a, a_reg);
+        let b = self.operand(pc, instr.b, b_reg);
+        let is_gt = matches!(instr.opcode, Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr);
+        // Put the register first, so "256 > r1" reads as "r1 < 256".
+        let (lhs, rhs, ops) = match (is_gt, a_reg) {
+            (true, false) => (b, a, ("<", ">=")),
+            (true, true) => (a, b, (">", "<=")),
+            (false, false) => (b, a, ("==", "!=")),
+            (false, true) => (a, b, ("==", "!=")),
+        };
+        Cond {
+            text: format!("{} {} {}", lhs, ops.0, rhs),
+            negated: format!("{} {} {}", lhs, ops.1, rhs),
+        }
+    }
+
+    fn statement(&self, pc: usize, instr: &Instruction) -> String {
+        let c = reg(instr.c);
+        let (a_reg, b_reg) = instr.opcode.reg_operands();
+        let commutative = matches!(
+            instr.opcode,
+            Opcode::Addr | Opcode::Mulr | Opcode::Banr | Opcode::Borr
+        );
+        let arith = !instr.opcode.is_comparison() && !matches!(instr.opcode, Opcode::Setr | Opcode::Seti);
+        if arith && a_reg && instr.a == instr.c {
+            let b = self.operand(pc, instr.b, b_reg);
+            return format!("{} {}= {}", c, op(instr.opcode), b);
+        }
+        if arith && commutative && instr.b == instr.c {
+            let a = self.operand(pc, instr.a, a_reg);
+            return format!("{} {}= {}", c, op(instr.opcode), a);
+        }
+        format!("{} = {}", c, self.expr(pc, instr))
+    }
+
+    // How the instruction at "pc" affects control flow, if it writes
+    // to the instruction pointer.
+    fn control(&self, pc: usize) -> Option<Term> {
+        let instrs = self.program.instrs();
+        let instr = &instrs[pc];
+        if !self.is_ip(instr.c) {
+            return None;
+        }
+        if let Some(val) = self.constant(pc, instr) {
+            return Some(Term::Goto(val.saturating_add(1)));
+        }
+        if instr.opcode == Opcode::Addr && (self.is_ip(instr.a) != self.is_ip(instr.b)) {
+            let r = if self.is_ip(instr.a) { instr.b } else { instr.a };
+            let is_flag = pc > 0 && instrs[pc - 1].opcode.is_comparison() && instrs[pc - 1].c == r;
+            if is_flag {
+                return Some(Term::SkipIf(r));
+            }
+            return Some(Term::Computed(format!("{} + {}", pc + 1, reg(r))));
+        }
+        Some(Term::Computed(format!("({}) + 1", self.expr(pc, instr))))
+    }
+
+    // Addresses that control can go to from the end of a block,
+    // including ones off the end of the program.
+    fn targets(&self, block: &Block) -> Vec<usize> {
+        let last = block.end - 1;
+        match block.term {
+            Term::Fall => vec![block.end],
+            Term::Goto(t) => vec![t],
+            Term::SkipIf(_) => vec![last + 1, last + 2],
+            Term::Computed(_) => {
+                let instr = &self.program.instrs()[last];
+                if instr.opcode == Opcode::Addr {
+                    vec![last + 1, last + 2]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+
+    // Whether the program might halt at the end of a block.
+    fn may_halt(&self, block: &Block) -> bool {
+        let targets = self.targets(block);
+        targets.is_empty() || targets.iter().any(|t| *t >= self.len())
+    }
+
+    fn successors(&self, block: &Block) -> Vec<usize> {
+        self.targets(block).into_iter().filter(|t| *t < self.len()).collect()
+    }
+
+    fn block_at(&self, addr: usize) -> Option<usize> {
+        self.blocks.iter().position(|b| b.start == addr)
+    }
+
+    // The start of the given block, or the end of the program.
+    fn addr_of(&self, block: usize) -> usize {
+        self.blocks.get(block).map(|b| b.start).unwrap_or_else(|| self.len())
+    }
+
+    fn find_blocks(&mut self) {
+        let len = self.len();
+        let mut leaders = BTreeSet::new();
+        leaders.insert(0);
+        let controls = (0..len).map(|pc| self.control(pc)).collect::<Vec<_>>();
+        for (pc, term) in controls.iter().enumerate() {
+            match term {
+                Some(Term::Goto(t)) => {
+                    leaders.insert(*t);
+                }
+                Some(_) => {
+                    leaders.insert(pc + 2);
+                }
+                None => continue,
+            }
+            leaders.insert(pc + 1);
+        }
+        let leaders = leaders.into_iter().filter(|l| *l < len).collect::<Vec<_>>();
+
+        for (i, start) in leaders.iter().enumerate() {
+            let limit = leaders.get(i + 1).cloned().unwrap_or(len);
+            let end = (*start..limit).find(|pc| controls[*pc].is_some()).map(|pc| pc + 1).unwrap_or(limit);
+            let term = controls[end - 1].clone().unwrap_or(Term::Fall);
+            self.blocks.push(Block { start: *start, end, term });
+        }
+    }
+
+    // Registers each instruction reads and writes, ignoring the
+    // instruction pointer.
+    fn uses(&self, instr: &Instruction) -> (Vec<usize>, Option<usize>) {
+        let (a_reg, b_reg) = instr.opcode.reg_operands();
+        let uses_b = !matches!(instr.opcode, Opcode::Setr | Opcode::Seti);
+        let mut reads = Vec::new();
+        if a_reg && !self.is_ip(instr.a) {
+            reads.push(instr.a);
+        }
+        if uses_b && b_reg && !self.is_ip(instr.b) {
+            reads.push(instr.b);
+        }
+        let write = if self.is_ip(instr.c) { None } else { Some(instr.c) };
+        (reads, write)
+    }
+
+    fn find_liveness(&mut self) {
+        let num_regs = self.program.num_regs();
+        let n = self.blocks.len();
+        // Only register 0 is live when the program halts, as that's
+        // where the puzzles leave their answers. Counting the rest
+        // would keep every flag around.
+        let mut live_in = vec![vec![false; num_regs]; n];
+        let succs = self
+            .blocks
+            .iter()
+            .map(|b| self.successors(b).iter().filter_map(|t| self.block_at(*t)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in (0..n).rev() {
+                let block = &self.blocks[i];
+                let mut live = vec![false; num_regs];
+                live[0] = self.may_halt(block);
+                for s in succs[i].iter() {
+                    for r in 0..num_regs {
+                        live[r] |= live_in[*s][r];
+                    }
+                }
+                for pc in (block.start..block.end).rev() {
+                    let (reads, write) = self.uses(&self.program.instrs()[pc]);
+                    if let Some(w) = write {
+                        live[w] = false;
+                    }
+                    for r in reads {
+                        live[r] = true;
+                    }
+                }
+                if live != live_in[i] {
+                    live_in[i] = live;
+                    changed = true;
+                }
+            }
+        }
+        self.live_in = live_in;
+    }
+
+    fn find_dominators(&mut self) {
+        let n = self.blocks.len();
+        let mut preds = vec![Vec::new(); n];
+        for (i, b) in self.blocks.iter().enumerate() {
+            for t in self.successors(b) {
+                if let Some(j) = self.block_at(t) {
+                    preds[j].push(i);
+                }
+            }
+        }
+        let mut doms = vec![vec![true; n]; n];
+        doms[0] = (0..n).map(|i| i == 0).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in 1..n {
+                let mut d = vec![!preds[i].is_empty(); n];
+                for p in preds[i].iter() {
+                    for (k, dk) in d.iter_mut().enumerate() {
+                        *dk &= doms[*p][k];
+                    }
+                }
+                d[i] = true;
+                if d != doms[i] {
+                    doms[i] = d;
+                    changed = true;
+                }
+            }
+        }
+        self.doms = doms;
+    }
+
+    // The condition for a skip at the end of a block, along with
+    // whether the comparison that set the flag can be left out
+    // because nothing else reads the flag.
+    fn skip_cond(&self, block: usize, r: usize) -> (Cond, bool) {
+        let b = &self.blocks[block];
+        let last = b.end - 1;
+        let flag_dead = self
+            .successors(b)
+            .iter()
+            .filter_map(|t| self.block_at(*t))
+            .all(|s| !self.live_in[s][r]);
+        if last > b.start && flag_dead {
+            let cmp = &self.program.instrs()[last - 1];
+            return (self.comparison(last - 1, cmp), true);
+        }
+        let cond = Cond {
+            text: format!("{} != 0", reg(r)),
+            negated: format!("{} == 0", reg(r)),
+        };
+        (cond, false)
+    }
+
+    // A jump to the given address, where "next" is where control goes
+    // without one.
+    fn jump(&mut self, target: usize, next: usize, ctx: Option<LoopCtx>, out: &mut Vec<Stmt>) {
+        // Anywhere off the end is the same place.
+        let target = target.min(self.len());
+        if target == next {
+            return;
+        }
+        if ctx.is_some_and(|c| c.head == target) {
+            out.push(Stmt::Continue);
+        } else if ctx.is_some_and(|c| c.exit == target) {
+            out.push(Stmt::Break);
+        } else if target == self.len() {
+            out.push(Stmt::Halt);
+        } else {
+            out.push(Stmt::Goto(target));
+        }
+    }
+
+    // The last block in [i, end) that jumps back to block i, where i
+    // dominates it, making a loop.
+    fn loop_end(&self, i: usize, end: usize) -> Option<usize> {
+        (i..end).rev().find(|j| {
+            self.doms[*j][i] && self.successors(&self.blocks[*j]).contains(&self.blocks[i].start)
+        })
+    }
+
+    // Decompile blocks [start, end). If "in_loop" is set, block
+    // "start" is the head of the loop being emitted, so isn't another.
+    fn region(&mut self, start: usize, end: usize, ctx: Option<LoopCtx>, in_loop: bool) -> Vec<Stmt> {
+        let mut out = Vec::new();
+        let mut i = start;
+        while i < end {
+            if !(in_loop && i == start) {
+                if let Some(j) = self.loop_end(i, end) {
+                    out.push(Stmt::Mark(self.blocks[i].start));
+                    let inner = LoopCtx {
+                        head: self.blocks[i].start,
+                        exit: self.addr_of(j + 1),
+                    };
+                    let mut body = self.region(i, j + 1, Some(inner), true);
+                    if !body.last().is_some_and(Stmt::is_jump) {
+                        body.push(Stmt::Break);
+                    }
+                    out.push(Stmt::Loop(body));
+                    i = j + 1;
+                    continue;
+                }
+            }
+
+            let (b_start, b_end, term) = {
+                let b = &self.blocks[i];
+                (b.start, b.end, b.term.clone())
+            };
+            if !(in_loop && i == start) {
+                out.push(Stmt::Mark(b_start));
+            }
+            let (cond, skip_cmp) = match term {
+                Term::SkipIf(r) => {
+                    let (cond, skip_cmp) = self.skip_cond(i, r);
+                    (Some(cond), skip_cmp)
+                }
+                _ => (None, false),
+            };
+            let body_end = match term {
+                Term::Fall => b_end,
+                _ if skip_cmp => b_end - 2,
+                _ => b_end - 1,
+            };
+            for pc in b_start..body_end {
+                let instr = self.program.instrs()[pc];
+                out.push(Stmt::Assign(self.statement(pc, &instr)));
+            }
+
+            match term {
+                Term::Fall => {}
+                Term::Goto(t) => {
+                    let next = self.addr_of(i + 1);
+                    self.jump(t, next, ctx, &mut out);
+                }
+                Term::Computed(text) => out.push(Stmt::Computed(text)),
+                Term::SkipIf(_) => {
+                    let cond = cond.unwrap();
+                    let skip_to = b_end + 1;
+                    match self.block_at(skip_to).filter(|k| *k > i + 1 && *k <= end) {
+                        Some(k) => {
+                            i = self.if_else(i, k, end, cond, ctx, &mut out);
+                            continue;
+                        }
+                        None => {
+                            let mut then = Vec::new();
+                            self.jump(skip_to, usize::MAX, ctx, &mut then);
+                            out.push(Stmt::If(cond, then, Vec::new()));
+                        }
+                    }
+                }
+            }
+            i += 1;
+        }
+        out
+    }
+
+    // Block "i" skips to block "k" if "cond" holds, and otherwise runs
+    // the blocks in between. If those end by jumping forward past "k",
+    // it's an if/else. Returns the block to carry on from.
+    fn if_else(&mut self, i: usize, k: usize, end: usize, cond: Cond, ctx: Option<LoopCtx>, out: &mut Vec<Stmt>) -> usize {
+        let last = &self.blocks[k - 1];
+        let else_end = match last.term {
+            Term::Goto(t) if t > self.addr_of(k) => match self.block_at(t) {
+                Some(m) if m <= end => Some(m),
+                None if t >= self.len() && end == self.blocks.len() => Some(end),
+                _ => None,
+            },
+            _ => None,
+        };
+        match else_end {
+            Some(m) => {
+                // Drop the jump over the else part.
+                let then = self.region_without_last_jump(i + 1, k, ctx);
+                let els = self.region(k, m, ctx, false);
+                out.push(Stmt::If(cond.not(), then, els));
+                m
+            }
+            None => {
+                let then = self.region(i + 1, k, ctx, false);
+                out.push(Stmt::If(cond.not(), then, Vec::new()));
+                k
+            }
+        }
+    }
+
+    fn region_without_last_jump(&mut self, start: usize, end: usize, ctx: Option<LoopCtx>) -> Vec<Stmt> {
+        let mut res = self.region(start, end, ctx, false);
+        if res.last().is_some_and(Stmt::is_jump) {
+            res.pop();
+        }
+        res
+    }
+}
+
+fn op(opcode: Opcode) -> &'static str {
+    match opcode {
+        Opcode::Addr | Opcode::Addi => "+",
+        Opcode::Mulr | Opcode::Muli => "*",
+        Opcode::Banr | Opcode::Bani => "&",
+        Opcode::Borr | Opcode::Bori => "|",
+        _ => unreachable!(),
+    }
+}
+
+// Tidy loops into while and do-while where they fit, and drop
+// redundant bits.
+fn simplify(stmts: Vec<Stmt>) -> Vec<Stmt> {
+    stmts.into_iter().map(simplify_stmt).collect()
+}
+
+fn simplify_stmt(stmt: Stmt) -> Stmt {
+    match stmt {
+        Stmt::If(cond, then, els) => {
+            let (then, els) = (simplify(then), simplify(els));
+            if then.is_empty() && !els.is_empty() {
+                Stmt::If(cond.not(), els, then)
+            } else {
+                Stmt::If(cond, then, els)
+            }
+        }
+        Stmt::Loop(body) => {
+            let mut body = simplify(body);
+            // "loop { ...; if (c) continue; break; }" is a do-while.
+            let n = body.len();
+            if n >= 2 && matches!(body[n - 1], Stmt::Break) {
+                if let Stmt::If(_, ref then, ref els) = body[n - 2] {
+                    if matches!(then[..], [Stmt::Continue]) && els.is_empty() {
+                        body.pop();
+                        if let Some(Stmt::If(cond, _, _)) = body.pop() {
+                            return Stmt::DoWhile(body, cond);
+                        }
+                    }
+                }
+            }
+            if matches!(body.last(), Some(Stmt::Continue)) {
+                body.pop();
+            }
+            // "loop { if (c) break; ... }" is a while.
+            if let Some(Stmt::If(_, then, els)) = body.first() {
+                let ends_loop = !matches!(body.last(), Some(Stmt::Break));
+                if matches!(then[..], [Stmt::Break]) && els.is_empty() && ends_loop {
+                    if let Stmt::If(cond, _, _) = body.remove(0) {
+                        return Stmt::While(cond.not(), body);
+                    }
+                }
+            }
+            Stmt::Loop(body)
+        }
+        s => s,
+    }
+}
+
+fn print(stmts: &[Stmt], gotos: &BTreeSet<usize>, depth: usize, out: &mut String) {
+    let indent = "    ".repeat(depth);
+    for s in stmts.iter() {
+        match s {
+            Stmt::Assign(text) => writeln!(out, "{}{};", indent, text).unwrap(),
+            Stmt::If(cond, then, els) => {
+                writeln!(out, "{}if ({}) {{", indent, cond.text).unwrap();
+                print(then, gotos, depth + 1, out);
+                if !els.is_empty() {
+                    writeln!(out, "{}}} else {{", indent).unwrap();
+                    print(els, gotos, depth + 1, out);
+                }
+                writeln!(out, "{}}}", indent).unwrap();
+            }
+            Stmt::Loop(body) => {
+                writeln!(out, "{}loop {{", indent).unwrap();
+                print(body, gotos, depth + 1, out);
+                writeln!(out, "{}}}", indent).unwrap();
+            }
+            Stmt::While(cond, body) => {
+                writeln!(out, "{}while ({}) {{", indent, cond.text).unwrap();
+                print(body, gotos, depth + 1, out);
+                writeln!(out, "{}}}", indent).unwrap();
+            }
+            Stmt::DoWhile(body, cond) => {
+                writeln!(out, "{}do {{", indent).unwrap();
+                print(body, gotos, depth + 1, out);
+                writeln!(out, "{}}} while ({});", indent, cond.text).unwrap();
+            }
+            Stmt::Break => writeln!(out, "{}break;", indent).unwrap(),
+            Stmt::Continue => writeln!(out, "{}continue;", indent).unwrap(),
+            Stmt::Goto(t) => writeln!(out, "{}goto L{};", indent, t).unwrap(),
+            Stmt::Computed(text) => writeln!(out, "{}goto {};", indent, text).unwrap(),
+            Stmt::Halt => writeln!(out, "{}halt;", indent).unwrap(),
+            Stmt::Mark(addr) => {
+                if gotos.contains(addr) {
+                    // Labels stick out to the left, like in the listings.
+                    writeln!(out, "L{}:", addr).unwrap();
+                }
+            }
+        }
+    }
+}
+
+// The addresses jumped to by gotos, which need labels.
+fn find_gotos(stmts: &[Stmt], gotos: &mut BTreeSet<usize>) {
+    for s in stmts.iter() {
+        match s {
+            Stmt::Goto(t) => {
+                gotos.insert(*t);
+            }
+            Stmt::If(_, a, b) => {
+                find_gotos(a, gotos);
+                find_gotos(b, gotos);
+            }
+            Stmt::Loop(body) | Stmt::While(_, body) | Stmt::DoWhile(body, _) => find_gotos(body, gotos),
+            _ => {}
+        }
+    }
+}
+
+// Remove the starts of blocks that don't need labels, so they don't
+// get in the way of tidying up.
+fn strip_marks(stmts: Vec<Stmt>, gotos: &BTreeSet<usize>) -> Vec<Stmt> {
+    stmts
+        .into_iter()
+        .filter(|s| !matches!(s, Stmt::Mark(a) if !gotos.contains(a)))
+        .map(|s| match s {
+            Stmt::If(c, a, b) => Stmt::If(c, strip_marks(a, gotos), strip_marks(b, gotos)),
+            Stmt::Loop(body) => Stmt::Loop(strip_marks(body, gotos)),
+            s => s,
+        })
+        .collect()
+}
+
+// Decompile the program to pseudo-code.
+pub fn decompile(program: &Program) -> String {
+    let mut d = Decompiler::new(program);
+    let n = d.blocks.len();
+    let stmts = d.region(0, n, None, false);
+    let mut gotos = BTreeSet::new();
+    find_gotos(&stmts, &mut gotos);
+    // Computed jumps can land on the next two instructions, so label
+    // those.
+    for b in d.blocks.iter() {
+        if let Term::Computed(_) = b.term {
+            gotos.extend(d.successors(b));
+        }
+    }
+    let stmts = simplify(strip_marks(stmts, &gotos));
+    let mut out = String::new();
+    print(&stmts, &gotos, 0, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asm::assemble;
+
+    #[test]
+    fn do_while() {
+        let program = assemble(
+            "#ip 2
+            seti 0 0 1
+    top:    seti 123 0 4
+            bani 4 456 4
+            eqri 4 72 4
+            addr 4 pc pc
+            seti top 0 pc
+            seti 0 1 4
+",
+            6,
+        ).unwrap();
+        assert_eq!(
+            decompile(&program),
+            "r1 = 0;
+do {
+    r4 = 123;
+    r4 &= 456;
+} while (r4 != 72);
+r4 = 0;
+"
+        );
+    }
+
+    #[test]
+    fn while_and_if() {
+        // r1 = 0; while (r1 <= 9) { if (r1 == 5) r0 += 1; r1 += 1 }
+        let program = assemble(
+            "#ip 5
+            seti 0 0 1
+    top:    gtri 1 9 2
+            addr 2 pc pc
+            addi pc 1 pc
+            seti 100 0 pc
+            eqri 1 5 2
+            addr 2 pc pc
+            addi pc 1 pc
+            addi 0 1 0
+            addi 1 1 1
+            seti top 0 pc
+",
+            6,
+        ).unwrap();
+        assert_eq!(
+            decompile(&program),
+            "r1 = 0;
+while (r1 <= 9) {
+    if (r1 == 5) {
+        r0 += 1;
+    }
+    r1 += 1;
+}
+"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+// Find the values of a register that would make a program halt, as in
+// day 21.
+//
+// Programs like day 21's only read the register in one place: an
+// equality test that ends the program when it matches. Which values
+// it's compared against doesn't depend on the register, so run the
+// program, noting each value as it's compared, and force the test to
+// fail so that it carries on. Once a value comes round again, the
+// comparisons repeat, so every value that can ever stop it has been
+// seen.
+
+use machine::Machine;
+use opcode::Opcode;
+use optimise::Optimiser;
+use program::Program;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HaltingValues {
+    // The first value compared against, which halts the program
+    // soonest.
+    pub first: usize,
+    // The last new value before they repeat, which halts it latest
+    // while still halting.
+    pub last: usize,
+    // How many different values there were.
+    pub count: usize,
+}
+
+// The first equality test against register "reg", if any.
+pub fn find_check(program: &Program, reg: usize) -> Option<usize> {
+    program.instrs().iter().position(|instr| other_operand(instr.opcode, instr.a, instr.b, reg).is_some())
+}
+
+// For an equality test against "reg", the other operand, as a
+// register or immediate.
+fn other_operand(opcode: Opcode, a: usize, b: usize, reg: usize) -> Option<(usize, bool)> {
+    match opcode {
+        Opcode::Eqrr if a == reg => Some((b, true)),
+        Opcode::Eqrr if b == reg => Some((a, true)),
+        Opcode::Eqri if a == reg => Some((b, false)),
+        Opcode::Eqir if b == reg => Some((a, false)),
+        _ => None,
+    }
+}
+
+// Run the program, watching the equality test at "pc" against "reg",
+// until what it's compared against repeats. Fails if there's no such
+// test at "pc", or the program halts before getting to it at all.
+// Doesn't return if the program loops without reaching the test again.
+pub fn halting_values(program: &Program, pc: usize, reg: usize) -> Result<HaltingValues, String> {
+    let instr = *program.instrs().get(pc).ok_or_else(|| format!("No instruction {}", pc))?;
+    let (operand, is_reg) = other_operand(instr.opcode, instr.a, instr.b, reg)
+        .ok_or_else(|| format!("{} at {} isn't an equality test against register {}", instr, pc, reg))?;
+    let optimiser = Optimiser::new(program);
+    let mut machine = Machine::new(program);
+    let mut seen = HashSet::new();
+    let mut first = None;
+    let mut last = None;
+    loop {
+        if !machine.run_until(|m| m.pc() == pc) {
+            break;
+        }
+        let value = if is_reg { machine.regs()[operand] } else { operand };
+        if !seen.insert(value) {
+            break;
+        }
+        first = first.or(Some(value));
+        last = Some(value);
+        // Make sure it doesn't match, and carry on past it.
+        machine.regs_mut()[reg] = value.wrapping_add(1);
+        machine.step();
+        // The loops between tests are where the time goes.
+        while machine.pc() != pc && optimiser.step(&mut machine) {}
+    }
+    match (first, last) {
+        (Some(first), Some(last)) => Ok(HaltingValues {
+            first,
+            last,
+            count: seen.len(),
+        }),
+        _ => Err(format!("Halted before reaching {}", pc)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_values() {
+        // Compare r0 against x = (3x + 1) mod 16, starting from 0.
+        let program = Program::parse(
+            "#ip 5\nseti 0 0 1\nmuli 1 3 1\naddi 1 1 1\nbani 1 15 1\n\
+             eqrr 1 0 2\naddr 2 5 5\nseti 0 0 5\n",
+            6,
+        )
+        .unwrap();
+        assert_eq!(find_check(&program, 0), Some(4));
+        assert_eq!(find_check(&program, 3), None);
+        // 1, 4, 13, 8, 9, 12, 5, 0 and then 1 again.
+        let expected = HaltingValues {
+            first: 1,
+            last: 0,
+            count: 8,
+        };
+        assert_eq!(halting_values(&program, 4, 0), Ok(expected));
+    }
+
+    #[test]
+    fn never_compared() {
+        let program = Program::parse("#ip 5\nseti 7 0 5\neqrr 1 0 2\n", 6).unwrap();
+        assert_eq!(halting_values(&program, 1, 0), Err("Halted before reaching 1".to_string()));
+    }
+
+    #[test]
+    fn rejects_other_instructions() {
+        let program = Program::parse("#ip 5
+seti 7 0 5
+eqrr 1 0 2
+", 6).unwrap();
+        assert_eq!(halting_values(&program, 2, 0), Err("No instruction 2".to_string()));
+        assert!(halting_values(&program, 0, 0).is_err());
+        assert!(halting_values(&program, 1, 3).is_err());
+        assert!(halting_values(&program, 1, 6).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+// The "ElfCode" virtual machine used by days 16, 19 and 21: sixteen
+// opcodes working on a small bank of registers, with an optional
+// register bound to the instruction pointer.
+
+extern crate aoc_common;
+
+pub mod asm;
+pub mod bench;
+pub mod compile;
+pub mod debugger;
+pub mod decompile;
+pub mod halting;
+pub mod machine;
+pub mod mapping;
+pub mod opcode;
+pub mod optimise;
+pub mod program;
+pub mod samples;
+
+pub use compile::Compiled;
+pub use debugger::Debugger;
+pub use machine::Machine;
+pub use opcode::Opcode;
+pub use optimise::Optimiser;
+pub use program::{Instruction, Program};
@@ -0,0 +1,161 @@
+// A machine running a program, one instruction at a time.
+//
+// If the program binds the instruction pointer to a register, the
+// pointer is written to that register before each instruction, and
+// read back (plus one) afterwards. It's only bound while an
+// instruction executes, so the register isn't left incremented when
+// the program halts. The program halts when the instruction pointer
+// goes off the end.
+
+use program::{Instruction, Program};
+
+#[derive(Debug, Clone)]
+pub struct Machine<'a> {
+    program: &'a Program,
+    regs: Vec<usize>,
+    pc: usize,
+    // Instructions executed so far.
+    steps: u64,
+}
+
+impl<'a> Machine<'a> {
+    // A machine at the start of the program, with every register zero.
+    pub fn new(program: &'a Program) -> Machine<'a> {
+        Machine {
+            program,
+            regs: vec![0; program.num_regs()],
+            pc: 0,
+            steps: 0,
+        }
+    }
+
+    pub fn program(&self) -> &'a Program {
+        self.program
+    }
+
+    pub fn regs(&self) -> &[usize] {
+        &self.regs
+    }
+
+    pub fn regs_mut(&mut self) -> &mut [usize] {
+        &mut self.regs
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn set_pc(&mut self, pc: usize) {
+        self.pc = pc;
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    // The instruction that will run next, if not halted.
+    pub fn next_instr(&self) -> Option<&'a Instruction> {
+        self.program.instrs().get(self.pc)
+    }
+
+    // Execute a single instruction. Returns false, doing nothing, if
+    // the machine has already halted.
+    #[inline]
+    pub fn step(&mut self) -> bool {
+        let instr = match self.next_instr() {
+            Some(instr) => instr,
+            None => return false,
+        };
+        match self.program.ip() {
+            Some(ip) => {
+                self.regs[ip] = self.pc;
+                instr.execute(&mut self.regs);
+                self.pc = self.regs[ip].wrapping_add(1);
+            }
+            None => {
+                instr.execute(&mut self.regs);
+                self.pc += 1;
+            }
+        }
+        self.steps += 1;
+        true
+    }
+
+    // Carry on from "pc", counting "steps" instructions as run. For
+    // when the registers have been updated some other way, as if by
+    // running them.
+    pub fn advance(&mut self, pc: usize, steps: u64) {
+        self.pc = pc;
+        self.steps += steps;
+    }
+
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    // Run until the program halts, or "stop" returns true. "stop" is
+    // checked before each instruction, including the first, so it sees
+    // the machine as the instruction is about to run. Returns true if
+    // "stop" stopped it.
+    pub fn run_until<F>(&mut self, mut stop: F) -> bool
+    where
+        F: FnMut(&Machine) -> bool,
+    {
+        while !self.halted() {
+            if stop(self) {
+                return true;
+            }
+            self.step();
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The day 19 example.
+    const EXAMPLE: &str = "#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5
+";
+
+    #[test]
+    fn runs_example() {
+        let program = Program::parse(EXAMPLE, 6).unwrap();
+        let mut machine = Machine::new(&program);
+        machine.run();
+        assert_eq!(machine.regs(), &[6, 5, 6, 0, 0, 9]);
+        assert_eq!(machine.steps(), 5);
+        assert!(machine.halted());
+        assert!(!machine.step());
+    }
+
+    #[test]
+    fn stops_when_asked() {
+        let program = Program::parse(EXAMPLE, 6).unwrap();
+        let mut machine = Machine::new(&program);
+        assert!(machine.run_until(|m| m.pc() == 6));
+        assert_eq!(machine.regs(), &[5, 5, 6, 0, 0, 0]);
+        assert!(!machine.run_until(|m| m.pc() == 1));
+        assert_eq!(machine.regs()[5], 9);
+    }
+
+    #[test]
+    fn runs_without_ip() {
+        let program = Program::parse("seti 7 0 1\naddi 1 1 2\n", 4).unwrap();
+        let mut machine = Machine::new(&program);
+        machine.run();
+        assert_eq!(machine.regs(), &[0, 7, 8, 0]);
+    }
+}
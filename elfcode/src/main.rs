@@ -0,0 +1,258 @@
+extern crate aoc_common;
+extern crate elfcode;
+
+use aoc_common::Error;
+use elfcode::{asm, bench, decompile, halting, samples};
+use elfcode::{Debugger, Machine, Optimiser, Program};
+use std::env;
+use std::fs;
+use std::io;
+use std::io::{BufRead, Write};
+use std::process;
+
+const USAGE: &str = "Usage:
+    elfcode run <program> [--regs <n>] [--set <reg>=<value>]... [--plain]
+    elfcode debug <program> [--regs <n>] [--set <reg>=<value>]...
+    elfcode bench <program> [--regs <n>] [--set <reg>=<value>]...
+    elfcode halting <program> [--regs <n>] [--reg <reg>]
+    elfcode asm <listing> [--regs <n>]
+    elfcode disasm <program> [--regs <n>]
+    elfcode decompile <program> [--regs <n>]
+    elfcode samples [--seed <n>] [--samples <n>] [--ambiguous <n>] [--program <n>]
+
+Programs may be plain or annotated listings, with labels and comments.
+`run` replaces the loops it recognises with closed-form versions,
+unless given --plain. `samples` makes up a day 16 input, printing the
+opcode numbering it used to stderr.";
+
+// Day 19 and 21's programs use six registers.
+const DEFAULT_REGS: usize = 6;
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("{}\n\n{}", msg, USAGE);
+    process::exit(2);
+}
+
+struct Options {
+    path: String,
+    num_regs: usize,
+    // Initial register values.
+    regs: Vec<(usize, usize)>,
+    // Don't optimise.
+    plain: bool,
+    // The register to find halting values of.
+    reg: usize,
+}
+
+fn parse_options(args: &[String]) -> Options {
+    let mut path = None;
+    let mut num_regs = DEFAULT_REGS;
+    let mut regs = Vec::new();
+    let mut plain = false;
+    let mut reg = 0;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--regs" => match iter.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => num_regs = n,
+                _ => usage_error("--regs needs a positive count"),
+            },
+            "--set" => {
+                let setting = iter.next().and_then(|s| {
+                    let mut parts = s.splitn(2, '=');
+                    let reg = parts.next()?.parse().ok()?;
+                    let value = parts.next()?.parse().ok()?;
+                    Some((reg, value))
+                });
+                match setting {
+                    Some(setting) => regs.push(setting),
+                    None => usage_error("--set needs <reg>=<value>"),
+                }
+            }
+            "--plain" => plain = true,
+            "--reg" => match iter.next().and_then(|r| r.parse().ok()) {
+                Some(r) => reg = r,
+                None => usage_error("--reg needs a register"),
+            },
+            _ if path.is_none() => path = Some(arg.clone()),
+            _ => usage_error("Too many arguments"),
+        }
+    }
+    let path = path.unwrap_or_else(|| usage_error("Need a program"));
+    if let Some((reg, _)) = regs.iter().find(|(reg, _)| *reg >= num_regs) {
+        usage_error(&format!("No register {}", reg));
+    }
+    if reg >= num_regs {
+        usage_error(&format!("No register {}", reg));
+    }
+    Options {
+        path,
+        num_regs,
+        regs,
+        plain,
+        reg,
+    }
+}
+
+fn read_program(opts: &Options) -> Result<Program, String> {
+    let text = fs::read_to_string(&opts.path).map_err(|e| format!("Couldn't read {}: {}", opts.path, e))?;
+    asm::assemble(&text, opts.num_regs).map_err(|e| Error::from(e).with_file(&opts.path).to_string())
+}
+
+fn cmd_run(args: &[String]) -> Result<(), String> {
+    let opts = parse_options(args);
+    let program = read_program(&opts)?;
+    let mut machine = Machine::new(&program);
+    for (reg, value) in opts.regs.iter() {
+        machine.regs_mut()[*reg] = *value;
+    }
+    if opts.plain {
+        machine.run();
+    } else {
+        Optimiser::new(&program).run(&mut machine);
+    }
+    println!("{:?}", machine.regs());
+    Ok(())
+}
+
+// Time the interpreter against compiled code, running the program to
+// the end with each.
+fn cmd_bench(args: &[String]) -> Result<(), String> {
+    let opts = parse_options(args);
+    let program = read_program(&opts)?;
+    let mut start = Machine::new(&program);
+    for (reg, value) in opts.regs.iter() {
+        start.regs_mut()[*reg] = *value;
+    }
+
+    let timing = bench::time(&start)?;
+    println!("{} steps, giving {:?}", timing.steps, timing.regs);
+    println!(
+        "interpreter: {:>10.3?} {:>6.2} ns/step",
+        timing.interpreted,
+        timing.per_step(timing.interpreted)
+    );
+    println!(
+        "compiled:    {:>10.3?} {:>6.2} ns/step (compiling took {:.3?}), {:.1}x faster",
+        timing.compiled,
+        timing.per_step(timing.compiled),
+        timing.compiling,
+        timing.speedup()
+    );
+    Ok(())
+}
+
+// Find the values of a register that would stop the program, by
+// watching the equality test against it.
+fn cmd_halting(args: &[String]) -> Result<(), String> {
+    let opts = parse_options(args);
+    let program = read_program(&opts)?;
+    let pc = halting::find_check(&program, opts.reg)
+        .ok_or_else(|| format!("Register {} is never tested for equality", opts.reg))?;
+    let values = halting::halting_values(&program, pc, opts.reg)?;
+    println!("Watching {} at {}", program.instrs()[pc], pc);
+    println!("{} values, first {}, last {}", values.count, values.first, values.last);
+    Ok(())
+}
+
+// Print the plain form of a listing.
+fn cmd_asm(args: &[String]) -> Result<(), String> {
+    let program = read_program(&parse_options(args))?;
+    print!("{}", program);
+    Ok(())
+}
+
+fn cmd_disasm(args: &[String]) -> Result<(), String> {
+    let program = read_program(&parse_options(args))?;
+    print!("{}", asm::disassemble(&program));
+    Ok(())
+}
+
+fn cmd_decompile(args: &[String]) -> Result<(), String> {
+    let program = read_program(&parse_options(args))?;
+    print!("{}", decompile::decompile(&program));
+    Ok(())
+}
+
+// Make up a day 16 input. Everything comes from the seed, including
+// the opcode numbering.
+fn cmd_samples(args: &[String]) -> Result<(), String> {
+    let mut seed = 0;
+    let mut num_samples = 800;
+    let mut ambiguous = 400;
+    let mut program_len = 1000;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = match arg.as_str() {
+            "--seed" => &mut seed,
+            "--samples" => &mut num_samples,
+            "--ambiguous" => &mut ambiguous,
+            "--program" => &mut program_len,
+            _ => usage_error(&format!("Unknown option: {}", arg)),
+        };
+        match iter.next().and_then(|n| n.parse().ok()) {
+            Some(n) => *value = n,
+            None => usage_error(&format!("{} needs a number", arg)),
+        }
+    }
+    if ambiguous > num_samples {
+        usage_error("More ambiguous samples than samples");
+    }
+
+    let mut rng = samples::Rng::new(seed as u64);
+    let mapping = samples::random_mapping(&mut rng);
+    let gen = samples::generate(&mut rng, &mapping, num_samples, ambiguous, program_len)
+        .ok_or("Couldn't find samples of the kind wanted")?;
+    let names = mapping.iter().map(|op| op.name()).collect::<Vec<_>>();
+    eprintln!("Mapping: {}", names.join(" "));
+    eprintln!("Result: {}", gen.result());
+    print!("{}", gen);
+    Ok(())
+}
+
+fn cmd_debug(args: &[String]) -> Result<(), String> {
+    let opts = parse_options(args);
+    let program = read_program(&opts)?;
+    let mut debugger = Debugger::new(&program);
+    for (reg, value) in opts.regs.iter() {
+        debugger.command(&format!("set {} {}", reg, value));
+    }
+    println!("{}", debugger.command("regs").unwrap());
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("(elfdb) ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| e.to_string())?,
+            None => break,
+        };
+        match debugger.command(&line) {
+            Some(ref out) if out.is_empty() => {}
+            Some(out) => println!("{}", out),
+            None => break,
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let res = match args.first().map(String::as_str) {
+        Some("run") => cmd_run(&args[1..]),
+        Some("debug") => cmd_debug(&args[1..]),
+        Some("bench") => cmd_bench(&args[1..]),
+        Some("halting") => cmd_halting(&args[1..]),
+        Some("asm") => cmd_asm(&args[1..]),
+        Some("disasm") => cmd_disasm(&args[1..]),
+        Some("decompile") => cmd_decompile(&args[1..]),
+        Some("samples") => cmd_samples(&args[1..]),
+        Some(cmd) => usage_error(&format!("Unknown command: {}", cmd)),
+        None => usage_error("No command given"),
+    };
+    if let Err(msg) = res {
+        eprintln!("{}", msg);
+        process::exit(1);
+    }
+}
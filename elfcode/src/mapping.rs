@@ -0,0 +1,298 @@
+// Work out which opcode number is which opcode, as in day 16.
+//
+// Each sample says which opcodes fit what one instruction did to the
+// registers. Samples can be missing or wrong, so for each number the
+// candidates are the opcodes that fit the most of its samples, and a
+// sample that fits none of them is contradictory. When all the samples
+// are right, that's just the opcodes that fit every one.
+//
+// Then it's a matter of matching numbers to opcodes one-to-one, by
+// propagating the singletons (a number with only one opcode left, or
+// an opcode with only one number left) and backtracking when that
+// runs out.
+
+use opcode::Opcode;
+use std::collections::BTreeSet;
+
+pub const NUM_OPCODES: usize = 16;
+
+// A set of opcodes, one bit each, in the order of Opcode::ALL.
+type Domain = u16;
+
+const ALL: Domain = 0xffff;
+
+fn to_domain(opcodes: &BTreeSet<Opcode>) -> Domain {
+    opcodes.iter().fold(0, |acc, &op| acc | 1 << op as usize)
+}
+
+fn from_domain(domain: Domain) -> BTreeSet<Opcode> {
+    Opcode::ALL
+        .iter()
+        .cloned()
+        .filter(|&op| domain & 1 << op as usize != 0)
+        .collect()
+}
+
+// The only opcode in a singleton domain.
+fn single(domain: Domain) -> Option<Opcode> {
+    if domain.count_ones() == 1 {
+        Some(Opcode::ALL[domain.trailing_zeros() as usize])
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    // For each number, the opcodes that fit most of its samples.
+    pub candidates: Vec<BTreeSet<Opcode>>,
+    // For each number, the opcodes it is in some complete mapping.
+    pub possible: Vec<BTreeSet<Opcode>>,
+    // Complete mappings from number to opcode, up to the limit asked
+    // for.
+    pub mappings: Vec<Vec<Opcode>>,
+    // Indices of the samples that fit none of their number's
+    // candidates.
+    pub contradictory: Vec<usize>,
+}
+
+impl Analysis {
+    // The numbers that could be more than one opcode.
+    pub fn ambiguous(&self) -> Vec<usize> {
+        (0..NUM_OPCODES).filter(|&n| self.possible[n].len() > 1).collect()
+    }
+
+    // The mapping, if there's exactly one.
+    pub fn unique(&self) -> Option<&[Opcode]> {
+        if self.mappings.len() == 1 && self.ambiguous().is_empty() {
+            Some(&self.mappings[0])
+        } else {
+            None
+        }
+    }
+}
+
+// Narrow the domains down using the singletons. Returns false if some
+// number or opcode is left with nowhere to go.
+fn propagate(domains: &mut [Domain]) -> bool {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        // A number that must be an opcode means no other number is.
+        for n in 0..NUM_OPCODES {
+            if domains[n] == 0 {
+                return false;
+            }
+            if domains[n].count_ones() != 1 {
+                continue;
+            }
+            let bit = domains[n];
+            for (m, domain) in domains.iter_mut().enumerate() {
+                if m != n && *domain & bit != 0 {
+                    *domain &= !bit;
+                    changed = true;
+                }
+            }
+        }
+        // An opcode that only one number can be must be that number.
+        for i in 0..NUM_OPCODES {
+            let bit = 1 << i;
+            let mut numbers = (0..NUM_OPCODES).filter(|&n| domains[n] & bit != 0);
+            match (numbers.next(), numbers.next()) {
+                (None, _) => return false,
+                (Some(n), None) if domains[n] != bit => {
+                    domains[n] = bit;
+                    changed = true;
+                }
+                _ => (),
+            }
+        }
+    }
+    true
+}
+
+// Add the complete mappings within the domains to "found", stopping
+// once there are "limit" of them.
+fn search(domains: &mut [Domain], limit: usize, found: &mut Vec<Vec<Opcode>>) {
+    if found.len() >= limit || !propagate(domains) {
+        return;
+    }
+    // Branch on the number with fewest choices.
+    let n = match (0..NUM_OPCODES)
+        .filter(|&n| domains[n].count_ones() > 1)
+        .min_by_key(|&n| domains[n].count_ones())
+    {
+        Some(n) => n,
+        None => {
+            found.push(domains.iter().map(|&d| single(d).unwrap()).collect());
+            return;
+        }
+    };
+    for i in 0..NUM_OPCODES {
+        let bit = 1 << i;
+        if domains[n] & bit != 0 {
+            let mut trial = domains.to_vec();
+            trial[n] = bit;
+            search(&mut trial, limit, found);
+        }
+    }
+}
+
+// Record each number's opcode in the mapping as possible.
+fn note(mapping: &[Opcode], possible: &mut [Domain]) {
+    for (n, &op) in mapping.iter().enumerate() {
+        possible[n] |= 1 << op as usize;
+    }
+}
+
+// Analyse the samples, each given as its opcode number and the opcodes
+// that fit it, finding up to "limit" complete mappings.
+pub fn analyse(samples: &[(usize, BTreeSet<Opcode>)], limit: usize) -> Analysis {
+    let fits = samples
+        .iter()
+        .map(|(n, opcodes)| (*n, to_domain(opcodes)))
+        .collect::<Vec<_>>();
+
+    let mut candidates = vec![ALL; NUM_OPCODES];
+    for (n, candidate) in candidates.iter_mut().enumerate() {
+        let mut votes = [0; NUM_OPCODES];
+        for &(_, domain) in fits.iter().filter(|(m, _)| *m == n) {
+            for (i, vote) in votes.iter_mut().enumerate() {
+                if domain & 1 << i != 0 {
+                    *vote += 1;
+                }
+            }
+        }
+        // With no votes at all, there's nothing to go on.
+        let best = *votes.iter().max().unwrap();
+        if best > 0 {
+            *candidate = (0..NUM_OPCODES)
+                .filter(|&i| votes[i] == best)
+                .fold(0, |acc, i| acc | 1 << i);
+        }
+    }
+
+    let contradictory = fits
+        .iter()
+        .enumerate()
+        .filter(|(_, (n, domain))| domain & candidates[*n] == 0)
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut mappings = Vec::new();
+    search(&mut candidates.clone(), limit, &mut mappings);
+
+    // Which opcodes each number can be needs a search per choice, but
+    // every mapping found along the way settles a choice for each
+    // number.
+    let mut possible = vec![0; NUM_OPCODES];
+    for mapping in mappings.iter() {
+        note(mapping, &mut possible);
+    }
+    for n in 0..NUM_OPCODES {
+        for i in 0..NUM_OPCODES {
+            let bit = 1 << i;
+            if candidates[n] & bit == 0 || possible[n] & bit != 0 {
+                continue;
+            }
+            let mut trial = candidates.clone();
+            trial[n] = bit;
+            let mut found = Vec::new();
+            search(&mut trial, 1, &mut found);
+            if let Some(mapping) = found.first() {
+                note(mapping, &mut possible);
+            }
+        }
+    }
+
+    Analysis {
+        candidates: candidates.into_iter().map(from_domain).collect(),
+        possible: possible.into_iter().map(from_domain).collect(),
+        mappings,
+        contradictory,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(opcodes: &[Opcode]) -> BTreeSet<Opcode> {
+        opcodes.iter().cloned().collect()
+    }
+
+    // Samples pinning number n down to Opcode::ALL[n], except for the
+    // numbers given, which are left to their own samples.
+    fn identity_except(skip: &[usize]) -> Vec<(usize, BTreeSet<Opcode>)> {
+        (0..NUM_OPCODES)
+            .filter(|n| !skip.contains(n))
+            .map(|n| (n, set(&[Opcode::ALL[n]])))
+            .collect()
+    }
+
+    #[test]
+    fn unique_by_propagation() {
+        // Number 0 has to be addr once 1 is known to be addi.
+        let mut samples = identity_except(&[0]);
+        samples.push((0, set(&[Opcode::Addr, Opcode::Addi])));
+        let analysis = analyse(&samples, 10);
+        assert_eq!(analysis.unique(), Some(&Opcode::ALL[..]));
+        assert!(analysis.contradictory.is_empty());
+    }
+
+    #[test]
+    fn reports_ambiguity() {
+        // Numbers 0 and 1 could be either way round.
+        let mut samples = identity_except(&[0, 1]);
+        samples.push((0, set(&[Opcode::Addr, Opcode::Addi])));
+        samples.push((1, set(&[Opcode::Addr, Opcode::Addi, Opcode::Mulr])));
+        let analysis = analyse(&samples, 10);
+        assert_eq!(analysis.mappings.len(), 2);
+        assert_eq!(analysis.unique(), None);
+        assert_eq!(analysis.ambiguous(), vec![0, 1]);
+        assert_eq!(analysis.possible[1], set(&[Opcode::Addr, Opcode::Addi]));
+    }
+
+    #[test]
+    fn backtracks() {
+        // Nothing is a singleton, but there are only two ways to go
+        // round the cycle of four.
+        let mut samples = identity_except(&[0, 1, 2, 3]);
+        samples.push((0, set(&[Opcode::Addr, Opcode::Addi])));
+        samples.push((1, set(&[Opcode::Addi, Opcode::Mulr])));
+        samples.push((2, set(&[Opcode::Mulr, Opcode::Muli])));
+        samples.push((3, set(&[Opcode::Muli, Opcode::Addr])));
+        let analysis = analyse(&samples, 10);
+        assert_eq!(analysis.mappings.len(), 2);
+        assert_eq!(analysis.ambiguous(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn limits_mappings() {
+        // With no samples, anything goes.
+        let analysis = analyse(&[], 5);
+        assert_eq!(analysis.mappings.len(), 5);
+        assert!(analysis.possible.iter().all(|p| p.len() == NUM_OPCODES));
+    }
+
+    #[test]
+    fn outvotes_contradictions() {
+        let mut samples = identity_except(&[]);
+        samples.push((0, set(&[Opcode::Addr])));
+        samples.push((0, set(&[Opcode::Mulr])));
+        samples.push((2, set(&[])));
+        let analysis = analyse(&samples, 10);
+        assert_eq!(analysis.unique(), Some(&Opcode::ALL[..]));
+        assert_eq!(analysis.contradictory, vec![17, 18]);
+    }
+
+    #[test]
+    fn no_mapping() {
+        // Numbers 0 and 1 both have to be addr.
+        let mut samples = identity_except(&[1]);
+        samples.push((1, set(&[Opcode::Addr])));
+        let analysis = analyse(&samples, 10);
+        assert!(analysis.mappings.is_empty());
+        assert!(analysis.possible.iter().all(|p| p.is_empty()));
+    }
+}
@@ -0,0 +1,155 @@
+// The sixteen opcodes, and what they do to the registers.
+
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum Opcode {
+    Addr, // (add register) stores into register C the result of adding register A and register B.
+    Addi, // (add immediate) stores into register C the result of adding register A and value B.
+    Mulr, // (multiply register) stores into register C the result of multiplying register A and register B.
+    Muli, // (multiply immediate) stores into register C the result of multiplying register A and value B.
+    Banr, // (bitwise AND register) stores into register C the result of the bitwise AND of register A and register B.
+    Bani, // (bitwise AND immediate) stores into register C the result of the bitwise AND of register A and value B.
+    Borr, // (bitwise OR register) stores into register C the result of the bitwise OR of register A and register B.
+    Bori, // (bitwise OR immediate) stores into register C the result of the bitwise OR of register A and value B.
+    Setr, // (set register) copies the contents of register A into register C. (Input B is ignored.)
+    Seti, // (set immediate) stores value A into register C. (Input B is ignored.)
+    Gtir, // (greater-than immediate/register) sets register C to 1 if value A is greater than register B. Otherwise, register C is set to 0.
+    Gtri, // (greater-than register/immediate) sets register C to 1 if register A is greater than value B. Otherwise, register C is set to 0.
+    Gtrr, // (greater-than register/register) sets register C to 1 if register A is greater than register B. Otherwise, register C is set to 0.
+    Eqir, // (equal immediate/register) sets register C to 1 if value A is equal to register B. Otherwise, register C is set to 0.
+    Eqri, // (equal register/immediate) sets register C to 1 if register A is equal to value B. Otherwise, register C is set to 0.
+    Eqrr, // (equal register/register) sets register C to 1 if register A is equal to register B. Otherwise, register C is set to 0.
+}
+
+fn to_i(b: bool) -> usize {
+    if b {
+        1
+    } else {
+        0
+    }
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 16] = [
+        Opcode::Addr,
+        Opcode::Addi,
+        Opcode::Mulr,
+        Opcode::Muli,
+        Opcode::Banr,
+        Opcode::Bani,
+        Opcode::Borr,
+        Opcode::Bori,
+        Opcode::Setr,
+        Opcode::Seti,
+        Opcode::Gtir,
+        Opcode::Gtri,
+        Opcode::Gtrr,
+        Opcode::Eqir,
+        Opcode::Eqri,
+        Opcode::Eqrr,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Opcode::Addr => "addr",
+            Opcode::Addi => "addi",
+            Opcode::Mulr => "mulr",
+            Opcode::Muli => "muli",
+            Opcode::Banr => "banr",
+            Opcode::Bani => "bani",
+            Opcode::Borr => "borr",
+            Opcode::Bori => "bori",
+            Opcode::Setr => "setr",
+            Opcode::Seti => "seti",
+            Opcode::Gtir => "gtir",
+            Opcode::Gtri => "gtri",
+            Opcode::Gtrr => "gtrr",
+            Opcode::Eqir => "eqir",
+            Opcode::Eqri => "eqri",
+            Opcode::Eqrr => "eqrr",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Opcode> {
+        Opcode::ALL.iter().cloned().find(|op| op.name() == name)
+    }
+
+    // Whether operands A and B name registers, rather than being
+    // immediate values. C is always a register.
+    pub fn reg_operands(self) -> (bool, bool) {
+        match self {
+            Opcode::Addr | Opcode::Mulr | Opcode::Banr | Opcode::Borr => (true, true),
+            Opcode::Addi | Opcode::Muli | Opcode::Bani | Opcode::Bori => (true, false),
+            Opcode::Setr => (true, false),
+            Opcode::Seti => (false, false),
+            Opcode::Gtir | Opcode::Eqir => (false, true),
+            Opcode::Gtri | Opcode::Eqri => (true, false),
+            Opcode::Gtrr | Opcode::Eqrr => (true, true),
+        }
+    }
+
+    // Whether the result is always 0 or 1.
+    pub fn is_comparison(self) -> bool {
+        matches!(
+            self,
+            Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr | Opcode::Eqir | Opcode::Eqri | Opcode::Eqrr
+        )
+    }
+
+    // The value to store in register C. Arithmetic wraps rather than
+    // panicking, as the puzzle programs never rely on overflow.
+    #[inline]
+    pub fn apply(self, regs: &[usize], a: usize, b: usize) -> usize {
+        match self {
+            Opcode::Addr => regs[a].wrapping_add(regs[b]),
+            Opcode::Addi => regs[a].wrapping_add(b),
+            Opcode::Mulr => regs[a].wrapping_mul(regs[b]),
+            Opcode::Muli => regs[a].wrapping_mul(b),
+            Opcode::Banr => regs[a] & regs[b],
+            Opcode::Bani => regs[a] & b,
+            Opcode::Borr => regs[a] | regs[b],
+            Opcode::Bori => regs[a] | b,
+            Opcode::Setr => regs[a],
+            Opcode::Seti => a,
+            Opcode::Gtir => to_i(a > regs[b]),
+            Opcode::Gtri => to_i(regs[a] > b),
+            Opcode::Gtrr => to_i(regs[a] > regs[b]),
+            Opcode::Eqir => to_i(a == regs[b]),
+            Opcode::Eqri => to_i(regs[a] == b),
+            Opcode::Eqrr => to_i(regs[a] == regs[b]),
+        }
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for op in Opcode::ALL.iter() {
+            assert_eq!(Opcode::from_name(op.name()), Some(*op));
+        }
+        assert_eq!(Opcode::from_name("nope"), None);
+    }
+
+    #[test]
+    fn puzzle_example() {
+        // From day 16: "9 2 1 2" takes [3, 2, 1, 1] to [3, 2, 2, 1] for
+        // exactly mulr, addi and seti.
+        let regs = [3, 2, 1, 1];
+        let matching = Opcode::ALL
+            .iter()
+            .cloned()
+            .filter(|op| op.apply(&regs, 2, 1) == 2)
+            .collect::<Vec<_>>();
+        assert_eq!(matching, vec![Opcode::Addi, Opcode::Mulr, Opcode::Seti]);
+    }
+}
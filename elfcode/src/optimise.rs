@@ -0,0 +1,533 @@
+// Recognise the hot loops in the puzzle inputs, and run them as
+// single macro-ops.
+//
+// Each idiom is a template of instructions with variables for the
+// registers. Where a template matches, running from its first
+// instruction is replaced by a closed-form calculation, which leaves
+// the registers, instruction pointer and step count exactly as the
+// plain interpreter would. If the calculation's assumptions don't hold
+// (the arithmetic might wrap, say), it falls back to a plain step.
+
+use machine::Machine;
+use opcode::Opcode;
+use program::Program;
+
+// A template operand.
+#[derive(Debug, Clone, Copy)]
+enum Arg {
+    // A register, named by a variable. Different variables are
+    // different registers, and none is the instruction pointer.
+    Reg(usize),
+    // The register bound to the instruction pointer.
+    Ip,
+    // A particular value.
+    Imm(usize),
+    // A value, named by a variable of its own.
+    Const(usize),
+    // A value that jumps to the given instruction of the template.
+    Jump(usize),
+    // Anything. For operands that are ignored.
+    Any,
+}
+
+use self::Arg::*;
+
+type Template = [(Opcode, Arg, Arg, Arg)];
+
+// Register variables.
+const I: usize = 0;
+const J: usize = 1;
+const N: usize = 2;
+const ACC: usize = 3;
+const T: usize = 4;
+const K: usize = 0;
+const NUM_REGS: usize = 5;
+
+// Constant variables.
+const D: usize = 0;
+const X: usize = 1;
+const NUM_CONSTS: usize = 2;
+
+// do { if (i * j == n) acc += i; j += 1; } while (j <= n);
+const DIVISOR_TEST: &Template = &[
+    (Opcode::Mulr, Reg(I), Reg(J), Reg(T)),
+    (Opcode::Eqrr, Reg(T), Reg(N), Reg(T)),
+    (Opcode::Addr, Reg(T), Ip, Ip),
+    (Opcode::Addi, Ip, Imm(1), Ip),
+    (Opcode::Addr, Reg(I), Reg(ACC), Reg(ACC)),
+    (Opcode::Addi, Reg(J), Imm(1), Reg(J)),
+    (Opcode::Gtrr, Reg(J), Reg(N), Reg(T)),
+    (Opcode::Addr, Ip, Reg(T), Ip),
+    (Opcode::Seti, Jump(0), Any, Ip),
+];
+
+// The same, nested in do { j = 1; ...; i += 1; } while (i <= n);
+const DIVISOR_SUM: &Template = &[
+    (Opcode::Seti, Imm(1), Any, Reg(J)),
+    (Opcode::Mulr, Reg(I), Reg(J), Reg(T)),
+    (Opcode::Eqrr, Reg(T), Reg(N), Reg(T)),
+    (Opcode::Addr, Reg(T), Ip, Ip),
+    (Opcode::Addi, Ip, Imm(1), Ip),
+    (Opcode::Addr, Reg(I), Reg(ACC), Reg(ACC)),
+    (Opcode::Addi, Reg(J), Imm(1), Reg(J)),
+    (Opcode::Gtrr, Reg(J), Reg(N), Reg(T)),
+    (Opcode::Addr, Ip, Reg(T), Ip),
+    (Opcode::Seti, Jump(1), Any, Ip),
+    (Opcode::Addi, Reg(I), Imm(1), Reg(I)),
+    (Opcode::Gtrr, Reg(I), Reg(N), Reg(T)),
+    (Opcode::Addr, Ip, Reg(T), Ip),
+    (Opcode::Seti, Jump(0), Any, Ip),
+];
+
+// Division by repeated multiplication:
+// loop { t = (k + 1) * d; if (t > n) goto x + 1; k += 1; }
+const QUOTIENT: &Template = &[
+    (Opcode::Addi, Reg(K), Imm(1), Reg(T)),
+    (Opcode::Muli, Reg(T), Const(D), Reg(T)),
+    (Opcode::Gtrr, Reg(T), Reg(N), Reg(T)),
+    (Opcode::Addr, Reg(T), Ip, Ip),
+    (Opcode::Addi, Ip, Imm(1), Ip),
+    (Opcode::Seti, Const(X), Any, Ip),
+    (Opcode::Addi, Reg(K), Imm(1), Reg(K)),
+    (Opcode::Seti, Jump(0), Any, Ip),
+];
+
+// Multiplication by repeated addition:
+// do { acc += j; i += 1; } while (i <= n);
+const PRODUCT: &Template = &[
+    (Opcode::Addr, Reg(J), Reg(ACC), Reg(ACC)),
+    (Opcode::Addi, Reg(I), Imm(1), Reg(I)),
+    (Opcode::Gtrr, Reg(I), Reg(N), Reg(T)),
+    (Opcode::Addr, Ip, Reg(T), Ip),
+    (Opcode::Seti, Jump(0), Any, Ip),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Idiom {
+    DivisorTest,
+    DivisorSum,
+    Quotient,
+    Product,
+}
+
+impl Idiom {
+    pub const ALL: [Idiom; 4] = [Idiom::DivisorSum, Idiom::DivisorTest, Idiom::Quotient, Idiom::Product];
+
+    fn template(self) -> &'static Template {
+        match self {
+            Idiom::DivisorTest => DIVISOR_TEST,
+            Idiom::DivisorSum => DIVISOR_SUM,
+            Idiom::Quotient => QUOTIENT,
+            Idiom::Product => PRODUCT,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Idiom::DivisorTest => "divisor test",
+            Idiom::DivisorSum => "divisor sum",
+            Idiom::Quotient => "quotient",
+            Idiom::Product => "product",
+        }
+    }
+}
+
+// What a template's variables matched.
+#[derive(Debug, Clone, Copy, Default)]
+struct Vars {
+    regs: [Option<usize>; NUM_REGS],
+    consts: [Option<usize>; NUM_CONSTS],
+}
+
+impl Vars {
+    fn reg(&self, v: usize) -> usize {
+        self.regs[v].unwrap()
+    }
+
+    fn constant(&self, v: usize) -> usize {
+        self.consts[v].unwrap()
+    }
+}
+
+// An idiom found in a program.
+#[derive(Debug, Clone, Copy)]
+struct Match {
+    idiom: Idiom,
+    vars: Vars,
+}
+
+// Try to match a template at "start", returning the variables.
+fn match_template(program: &Program, start: usize, template: &Template) -> Option<Vars> {
+    let ip = program.ip()?;
+    let instrs = program.instrs().get(start..start + template.len())?;
+    let mut vars = Vars::default();
+    for (instr, &(opcode, a, b, c)) in instrs.iter().zip(template.iter()) {
+        if instr.opcode != opcode {
+            return None;
+        }
+        let (a_reg, b_reg) = opcode.reg_operands();
+        let commutative = matches!(
+            opcode,
+            Opcode::Addr | Opcode::Mulr | Opcode::Banr | Opcode::Borr | Opcode::Eqrr
+        );
+        let mut try_order = |x, y| {
+            let mut v = vars;
+            let ok = bind(&mut v, a, x, a_reg, ip, start)
+                && bind(&mut v, b, y, b_reg, ip, start)
+                && bind(&mut v, c, instr.c, true, ip, start);
+            if ok {
+                vars = v;
+            }
+            ok
+        };
+        let matched = try_order(instr.a, instr.b) || (commutative && try_order(instr.b, instr.a));
+        if !matched {
+            return None;
+        }
+    }
+    Some(vars)
+}
+
+fn bind(vars: &mut Vars, arg: Arg, val: usize, is_reg: bool, ip: usize, start: usize) -> bool {
+    match arg {
+        Reg(v) => {
+            if !is_reg || val == ip {
+                return false;
+            }
+            match vars.regs[v] {
+                Some(bound) => bound == val,
+                None => {
+                    let clash = vars.regs.contains(&Some(val));
+                    vars.regs[v] = Some(val);
+                    !clash
+                }
+            }
+        }
+        Ip => is_reg && val == ip,
+        Imm(x) => !is_reg && val == x,
+        Const(v) => {
+            if is_reg {
+                return false;
+            }
+            match vars.consts[v] {
+                Some(bound) => bound == val,
+                None => {
+                    vars.consts[v] = Some(val);
+                    true
+                }
+            }
+        }
+        Jump(k) => !is_reg && start + k > 0 && val == start + k - 1,
+        Any => true,
+    }
+}
+
+pub struct Optimiser {
+    // Indexed by instruction: the idiom starting there, if any.
+    idioms: Vec<Option<Match>>,
+}
+
+impl Optimiser {
+    pub fn new(program: &Program) -> Optimiser {
+        let idioms = (0..program.len())
+            .map(|pc| {
+                Idiom::ALL.iter().find_map(|idiom| {
+                    match_template(program, pc, idiom.template()).map(|vars| Match { idiom: *idiom, vars })
+                })
+            })
+            .collect();
+        Optimiser { idioms }
+    }
+
+    // Where idioms were found, and which.
+    pub fn idioms(&self) -> Vec<(usize, Idiom)> {
+        self.idioms
+            .iter()
+            .enumerate()
+            .filter_map(|(pc, m)| m.map(|m| (pc, m.idiom)))
+            .collect()
+    }
+
+    // Run one instruction, or a whole idiom if one starts here.
+    // Returns false if the machine has halted.
+    #[inline]
+    pub fn step(&self, machine: &mut Machine) -> bool {
+        let pc = machine.pc();
+        if let Some(Some(m)) = self.idioms.get(pc) {
+            // On a copy, so nothing's changed if the step count would
+            // overflow.
+            let mut regs = machine.regs().to_vec();
+            if let Some((exit, steps)) = apply(m, pc, &mut regs) {
+                if machine.steps().checked_add(steps).is_some() {
+                    let ip = machine.program().ip().unwrap();
+                    // The jump out leaves its own address in the register.
+                    regs[ip] = exit - 1;
+                    machine.regs_mut().copy_from_slice(&regs);
+                    machine.advance(exit, steps);
+                    return true;
+                }
+            }
+        }
+        machine.step()
+    }
+
+    pub fn run(&self, machine: &mut Machine) {
+        assert_eq!(self.idioms.len(), machine.program().len());
+        while self.step(machine) {}
+    }
+}
+
+// Run an idiom starting at "pc" on the registers, returning where it
+// exits and how many instructions it took, or None if it can't.
+fn apply(m: &Match, pc: usize, regs: &mut [usize]) -> Option<(usize, u64)> {
+    let v = &m.vars;
+    match m.idiom {
+        Idiom::DivisorTest => {
+            let (i, j, n) = (regs[v.reg(I)], regs[v.reg(J)], regs[v.reg(N)]);
+            // It's a do-while, so goes round at least once.
+            let end = j.max(n).checked_add(1)?;
+            // Nothing may wrap.
+            i.checked_mul(end)?;
+            let iters = (end - j) as u64;
+            let hits = match i {
+                0 if n == 0 => iters,
+                0 => 0,
+                _ if n % i == 0 && j <= n / i && n / i < end => 1,
+                _ => 0,
+            };
+            regs[v.reg(ACC)] = regs[v.reg(ACC)].wrapping_add(i.wrapping_mul(hits as usize));
+            regs[v.reg(J)] = end;
+            regs[v.reg(T)] = 1;
+            // Eight instructions a time round, hit or not, and one
+            // fewer at the end for not jumping back.
+            Some((pc + DIVISOR_TEST.len(), iters.checked_mul(8)? - 1))
+        }
+        Idiom::DivisorSum => {
+            let (i, n) = (regs[v.reg(I)], regs[v.reg(N)]);
+            if n == 0 {
+                return None;
+            }
+            let end = i.max(n).checked_add(1)?;
+            end.checked_mul(n.checked_add(1)?)?;
+            let iters = (end - i) as u64;
+            // The inner loop, plus five around it.
+            let per_iter = (n as u64).checked_mul(8)?.checked_add(4)?;
+            let steps = iters.checked_mul(per_iter)? - 1;
+            // Each i from the start that divides n is added once,
+            // wrapping as the instructions would.
+            let mut sum: usize = 0;
+            let mut d = 1;
+            while d <= n / d {
+                if n % d == 0 {
+                    if d >= i {
+                        sum = sum.wrapping_add(d);
+                    }
+                    if n / d != d && n / d >= i {
+                        sum = sum.wrapping_add(n / d);
+                    }
+                }
+                d += 1;
+            }
+            regs[v.reg(ACC)] = regs[v.reg(ACC)].wrapping_add(sum);
+            regs[v.reg(I)] = end;
+            regs[v.reg(J)] = n + 1;
+            regs[v.reg(T)] = 1;
+            Some((pc + DIVISOR_SUM.len(), steps))
+        }
+        Idiom::Quotient => {
+            let (k, n, d) = (regs[v.reg(K)], regs[v.reg(N)], v.constant(D));
+            // Otherwise it never stops.
+            if d == 0 {
+                return None;
+            }
+            let end = k.max(n / d);
+            end.checked_add(1)?.checked_mul(d)?;
+            // Seven instructions a time round, and five to get out.
+            let steps = ((end - k) as u64).checked_mul(7)?.checked_add(5)?;
+            let exit = v.constant(X).checked_add(1)?;
+            regs[v.reg(K)] = end;
+            regs[v.reg(T)] = 1;
+            Some((exit, steps))
+        }
+        Idiom::Product => {
+            let (i, n) = (regs[v.reg(I)], regs[v.reg(N)]);
+            // Once round even if i is already past n.
+            let iters = n.saturating_sub(i).checked_add(1)?;
+            let end = i.checked_add(iters)?;
+            // Five instructions a time round, and one fewer at the end.
+            let steps = (iters as u64).checked_mul(5)? - 1;
+            // Adding wraps just as the instructions would.
+            regs[v.reg(ACC)] = regs[v.reg(ACC)].wrapping_add(regs[v.reg(J)].wrapping_mul(iters));
+            regs[v.reg(I)] = end;
+            regs[v.reg(T)] = 1;
+            Some((pc + PRODUCT.len(), steps))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asm::assemble;
+
+    // Day 19's loops, with a register to stop on.
+    const DIVISORS: &str = "#ip 2
+            seti 1 0 1
+    outer:  seti 1 3 3
+    inner:  mulr 1 3 5
+            eqrr 5 4 5
+            addr 5 2 2
+            addi 2 1 2
+            addr 1 0 0
+            addi 3 1 3
+            gtrr 3 4 5
+            addr 2 5 2
+            seti inner 6 2
+            addi 1 1 1
+            gtrr 1 4 5
+            addr 5 2 2
+            seti outer 1 2
+";
+
+    // Day 21's division by 256.
+    const QUOTIENTS: &str = "#ip 2
+            seti 0 0 3
+    top:    addi 3 1 5
+            muli 5 256 5
+            gtrr 5 1 5
+            addr 5 2 2
+            addi 2 1 2
+            seti done 0 2
+            addi 3 1 3
+            seti top 0 2
+    done:   setr 3 0 1
+";
+
+    // acc = j * (n + 1), by adding.
+    const PRODUCTS: &str = "#ip 2
+            seti 0 0 1
+    top:    addr 3 0 0
+            addi 1 1 1
+            gtrr 1 4 5
+            addr 2 5 2
+            seti top 0 2
+";
+
+    // Run from the given state both ways, and compare.
+    fn check(program: &Program, pc: usize, regs: &[usize]) {
+        let optimiser = Optimiser::new(program);
+        let mut plain = Machine::new(program);
+        plain.set_pc(pc);
+        plain.regs_mut().copy_from_slice(regs);
+        let mut fast = plain.clone();
+        plain.run();
+        optimiser.run(&mut fast);
+        assert_eq!(fast.regs(), plain.regs(), "from {} {:?}", pc, regs);
+        assert_eq!(fast.pc(), plain.pc(), "from {} {:?}", pc, regs);
+        assert_eq!(fast.steps(), plain.steps(), "from {} {:?}", pc, regs);
+    }
+
+    #[test]
+    fn finds_idioms() {
+        let program = assemble(DIVISORS, 6).unwrap();
+        let found = Optimiser::new(&program).idioms();
+        assert_eq!(found, vec![(1, Idiom::DivisorSum), (2, Idiom::DivisorTest)]);
+        let program = assemble(QUOTIENTS, 6).unwrap();
+        assert_eq!(Optimiser::new(&program).idioms(), vec![(1, Idiom::Quotient)]);
+        let program = assemble(PRODUCTS, 6).unwrap();
+        assert_eq!(Optimiser::new(&program).idioms(), vec![(1, Idiom::Product)]);
+    }
+
+    #[test]
+    fn matches_interpreter_on_divisors() {
+        let program = assemble(DIVISORS, 6).unwrap();
+        for n in 0..30 {
+            for acc in 0..2 {
+                check(&program, 0, &[acc, 0, 0, 0, n, 0]);
+            }
+            // Starting part way through.
+            for i in 0..5 {
+                for j in 0..5 {
+                    check(&program, 1, &[0, i, 0, j, n, 0]);
+                    check(&program, 2, &[7, i, 0, j, n, 3]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matches_interpreter_on_quotients() {
+        let program = assemble(QUOTIENTS, 6).unwrap();
+        for n in 0..1000 {
+            check(&program, 0, &[0, n, 0, 0, 0, 0]);
+            check(&program, 1, &[0, n, 0, 3, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn matches_interpreter_on_products() {
+        let program = assemble(PRODUCTS, 6).unwrap();
+        for n in 0..30 {
+            for j in 0..4 {
+                check(&program, 0, &[5, 0, 0, j, n, 0]);
+                // Starting part way through, maybe past n already.
+                for i in 0..40 {
+                    check(&program, 1, &[0, i, 0, j, n, 0]);
+                }
+            }
+        }
+        // Wrapping round, as the instructions do.
+        check(&program, 1, &[usize::MAX, 0, 0, usize::MAX / 2, 4, 0]);
+    }
+
+    #[test]
+    fn gives_up_where_it_would_overflow() {
+        // Takes just the one instruction, rather than the whole idiom.
+        let one_step = |listing: &str, pc, regs: &[usize]| {
+            let program = assemble(listing, 6).unwrap();
+            let optimiser = Optimiser::new(&program);
+            let mut machine = Machine::new(&program);
+            machine.regs_mut().copy_from_slice(regs);
+            machine.set_pc(pc);
+            assert!(optimiser.step(&mut machine));
+            assert_eq!(machine.steps(), 1, "{:?}", regs);
+        };
+        // Divisor sum: n + 1, and the step count.
+        one_step(DIVISORS, 1, &[0, 1, 0, 0, usize::MAX, 0]);
+        one_step(DIVISORS, 1, &[0, 1, 0, 0, 1 << 31, 0]);
+        // Divisor test: the step count.
+        one_step(DIVISORS, 2, &[0, 1, 0, 0, 1 << 62, 0]);
+        // Quotient: the step count, dividing by 1.
+        one_step(&QUOTIENTS.replace("muli 5 256 5", "muli 5 1 5"), 1, &[0, usize::MAX - 1, 0, 0, 0, 0]);
+        // Product: the number of times round, and the step count.
+        one_step(PRODUCTS, 1, &[0, 0, 0, 3, usize::MAX, 0]);
+        one_step(PRODUCTS, 1, &[0, 0, 0, 3, 1 << 62, 0]);
+    }
+
+    #[test]
+    fn gives_up_where_total_steps_would_overflow() {
+        let program = assemble(PRODUCTS, 6).unwrap();
+        let optimiser = Optimiser::new(&program);
+        let mut machine = Machine::new(&program);
+        machine.regs_mut()[4] = 10;
+        machine.advance(1, u64::MAX - 10);
+        assert!(optimiser.step(&mut machine));
+        assert_eq!(machine.steps(), u64::MAX - 9);
+        assert_eq!(machine.pc(), 2);
+    }
+
+    #[test]
+    fn leaves_other_code_alone() {
+        let program = assemble(QUOTIENTS.replace("muli 5 256 5", "muli 5 0 5").as_str(), 6).unwrap();
+        let optimiser = Optimiser::new(&program);
+        let mut machine = Machine::new(&program);
+        machine.regs_mut()[3] = 5;
+        machine.set_pc(1);
+        // Multiplying by zero never gets past n, so the loop is left
+        // to the interpreter.
+        for _ in 0..100 {
+            assert!(optimiser.step(&mut machine));
+        }
+        assert_eq!(machine.steps(), 100);
+    }
+}
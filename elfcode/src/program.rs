@@ -0,0 +1,183 @@
+// Instructions and whole programs, along with reading them from text
+// like:
+//
+//   #ip 0
+//   seti 5 0 1
+//   addi 0 1 0
+//
+// The "#ip" line is optional, and binds the instruction pointer to a
+// register.
+
+use std::fmt;
+
+use aoc_common::scan;
+use aoc_common::{ParseResult, Scanner};
+use opcode::Opcode;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+}
+
+// Read a register number, checking it's in range.
+fn register(s: &mut Scanner, num_regs: usize) -> ParseResult<usize> {
+    s.skip_whitespace();
+    let column = s.column();
+    let val: usize = s.number()?;
+    if val >= num_regs {
+        let expected = format!("a register number (0-{})", num_regs - 1);
+        return Err(s.error_at(column, &expected, &val.to_string()));
+    }
+    Ok(val)
+}
+
+fn operand(s: &mut Scanner, is_reg: bool, num_regs: usize) -> ParseResult<usize> {
+    if is_reg {
+        register(s, num_regs)
+    } else {
+        s.number()
+    }
+}
+
+impl Instruction {
+    pub fn new(opcode: Opcode, a: usize, b: usize, c: usize) -> Instruction {
+        Instruction { opcode, a, b, c }
+    }
+
+    // Instructions look like "addi 1 16 1". Register operands must be
+    // less than "num_regs".
+    pub fn read(s: &mut Scanner, num_regs: usize) -> ParseResult<Instruction> {
+        s.skip_whitespace();
+        let column = s.column();
+        let name = s.word()?;
+        let opcode = Opcode::from_name(name).ok_or_else(|| s.error_at(column, "an opcode", name))?;
+        let (a_reg, b_reg) = opcode.reg_operands();
+        let a = operand(s, a_reg, num_regs)?;
+        let b = operand(s, b_reg, num_regs)?;
+        let c = register(s, num_regs)?;
+        Ok(Instruction { opcode, a, b, c })
+    }
+
+    // Whether every register operand is less than "num_regs".
+    pub fn fits(&self, num_regs: usize) -> bool {
+        let (a_reg, b_reg) = self.opcode.reg_operands();
+        (!a_reg || self.a < num_regs) && (!b_reg || self.b < num_regs) && self.c < num_regs
+    }
+
+    #[inline]
+    pub fn execute(&self, regs: &mut [usize]) {
+        regs[self.c] = self.opcode.apply(regs, self.a, self.b);
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.opcode, self.a, self.b, self.c)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Program {
+    num_regs: usize,
+    // The register bound to the instruction pointer, if any.
+    ip: Option<usize>,
+    instrs: Vec<Instruction>,
+}
+
+impl Program {
+    // Panics if any instruction or the "#ip" binding uses a register
+    // that doesn't exist.
+    pub fn new(num_regs: usize, ip: Option<usize>, instrs: Vec<Instruction>) -> Program {
+        assert!(ip.is_none_or(|r| r < num_regs), "#ip register out of range");
+        assert!(instrs.iter().all(|i| i.fits(num_regs)), "Register out of range");
+        Program { num_regs, ip, instrs }
+    }
+
+    pub fn parse(input: &str, num_regs: usize) -> ParseResult<Program> {
+        let mut lines = scan::lines(input)
+            .filter(|s| !s.rest().trim().is_empty())
+            .peekable();
+
+        let mut ip = None;
+        if lines.peek().is_some_and(|s| s.rest().trim_start().starts_with('#')) {
+            let mut s = lines.next().unwrap();
+            s.literal("#ip")?;
+            ip = Some(register(&mut s, num_regs)?);
+            s.end()?;
+        }
+
+        let instrs = lines
+            .map(|mut s| {
+                let instr = Instruction::read(&mut s, num_regs)?;
+                s.end()?;
+                Ok(instr)
+            }).collect::<ParseResult<Vec<_>>>()?;
+        Ok(Program { num_regs, ip, instrs })
+    }
+
+    pub fn num_regs(&self) -> usize {
+        self.num_regs
+    }
+
+    pub fn ip(&self) -> Option<usize> {
+        self.ip
+    }
+
+    pub fn instrs(&self) -> &[Instruction] {
+        &self.instrs
+    }
+
+    pub fn len(&self) -> usize {
+        self.instrs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instrs.is_empty()
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ip) = self.ip {
+            writeln!(f, "#ip {}", ip)?;
+        }
+        for instr in self.instrs.iter() {
+            writeln!(f, "{}", instr)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\n";
+
+    #[test]
+    fn parse_and_display() {
+        let program = Program::parse(EXAMPLE, 6).unwrap();
+        assert_eq!(program.ip(), Some(0));
+        assert_eq!(program.len(), 3);
+        assert_eq!(program.instrs()[2], Instruction::new(Opcode::Addi, 0, 1, 0));
+        assert_eq!(program.to_string(), EXAMPLE);
+
+        let program = Program::parse("mulr 1 2 3\n", 4).unwrap();
+        assert_eq!(program.ip(), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let e = Program::parse("#ip 0\nseti 5 0 9\n", 6).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 10));
+        assert_eq!(e.expected, "a register number (0-5)");
+        // Immediate operands can be anything.
+        assert!(Program::parse("seti 5000 0 3\n", 4).is_ok());
+        assert!(Program::parse("seti 5 0 4\n", 4).is_err());
+        let e = Program::parse("seti 5 0 1\n#ip 0\n", 6).unwrap_err();
+        assert_eq!((e.line, e.expected.as_str()), (2, "a word"));
+    }
+}
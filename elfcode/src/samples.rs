@@ -0,0 +1,256 @@
+// Make up day 16 style inputs: samples of the registers before and
+// after an instruction, then a program, with the opcodes numbered by
+// a secret mapping.
+//
+// The samples are random, but how many of them fit three or more
+// opcodes (the count day 16 part a asks for) is chosen up front. Small
+// register values make a sample fit lots of opcodes, and big ones
+// make it fit few, so try random samples over a range of sizes until
+// they're the kind wanted.
+
+use mapping::NUM_OPCODES;
+use machine::Machine;
+use opcode::Opcode;
+use program::{Instruction, Program};
+use std::fmt;
+
+// Day 16's machine has four registers, and every operand is less than
+// four.
+const NUM_REGS: usize = 4;
+
+// How many random samples to try for each one wanted.
+const MAX_TRIES: usize = 1000;
+
+// A small, seedable xorshift generator, so that the same seed always
+// gives the same input.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // Zero is a fixed point, so mix the seed up first.
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // A value in 0..n.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+// A random numbering of the opcodes: the result's nth entry is the
+// opcode numbered n.
+pub fn random_mapping(rng: &mut Rng) -> Vec<Opcode> {
+    let mut mapping = Opcode::ALL.to_vec();
+    rng.shuffle(&mut mapping);
+    mapping
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub before: Vec<usize>,
+    // The opcode number and operands.
+    pub instr: [usize; 4],
+    pub after: Vec<usize>,
+}
+
+// The opcodes that would turn "before" into "after", given the
+// instruction as opcode number and operands. The number isn't looked
+// at, as it's what's being worked out.
+pub fn fitting(before: &[usize], instr: [usize; 4], after: &[usize]) -> Vec<Opcode> {
+    let [_, a, b, c] = instr;
+    Opcode::ALL
+        .iter()
+        .cloned()
+        .filter(|&op| {
+            let mut regs = before.to_vec();
+            Instruction::new(op, a, b, c).execute(&mut regs);
+            regs == after
+        })
+        .collect()
+}
+
+impl Sample {
+    pub fn fitting(&self) -> Vec<Opcode> {
+        fitting(&self.before, self.instr, &self.after)
+    }
+
+    pub fn is_ambiguous(&self) -> bool {
+        self.fitting().len() >= 3
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub mapping: Vec<Opcode>,
+    pub samples: Vec<Sample>,
+    // Instructions as opcode number and operands.
+    pub program: Vec<[usize; 4]>,
+}
+
+impl Generated {
+    // Register 0 after running the program, which is day 16 part b's
+    // answer.
+    pub fn result(&self) -> usize {
+        let instrs = self
+            .program
+            .iter()
+            .map(|&[n, a, b, c]| Instruction::new(self.mapping[n], a, b, c))
+            .collect();
+        let program = Program::new(NUM_REGS, None, instrs);
+        let mut machine = Machine::new(&program);
+        machine.run();
+        machine.regs()[0]
+    }
+}
+
+fn write_regs(f: &mut fmt::Formatter, label: &str, regs: &[usize]) -> fmt::Result {
+    let regs = regs.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+    writeln!(f, "{} [{}]", label, regs.join(", "))
+}
+
+fn write_instr(f: &mut fmt::Formatter, instr: &[usize; 4]) -> fmt::Result {
+    writeln!(f, "{} {} {} {}", instr[0], instr[1], instr[2], instr[3])
+}
+
+// In exactly the puzzle's format.
+impl fmt::Display for Generated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for sample in self.samples.iter() {
+            write_regs(f, "Before:", &sample.before)?;
+            write_instr(f, &sample.instr)?;
+            write_regs(f, "After: ", &sample.after)?;
+            writeln!(f)?;
+        }
+        writeln!(f)?;
+        writeln!(f)?;
+        for instr in self.program.iter() {
+            write_instr(f, instr)?;
+        }
+        Ok(())
+    }
+}
+
+fn random_instr(rng: &mut Rng) -> [usize; 4] {
+    [
+        rng.below(NUM_OPCODES),
+        rng.below(NUM_REGS),
+        rng.below(NUM_REGS),
+        rng.below(NUM_REGS),
+    ]
+}
+
+fn random_sample(rng: &mut Rng, mapping: &[Opcode]) -> Sample {
+    let instr = random_instr(rng);
+    // Values below 4, 8, 16 or 32.
+    let range = NUM_REGS << rng.below(4);
+    let before = (0..NUM_REGS).map(|_| rng.below(range)).collect::<Vec<_>>();
+    let mut after = before.clone();
+    Instruction::new(mapping[instr[0]], instr[1], instr[2], instr[3]).execute(&mut after);
+    Sample { before, instr, after }
+}
+
+// Make "num_samples" samples, "ambiguous" of which fit three or more
+// opcodes, and a program of "program_len" instructions. Returns None
+// if it can't find samples of the kind wanted.
+pub fn generate(
+    rng: &mut Rng,
+    mapping: &[Opcode],
+    num_samples: usize,
+    ambiguous: usize,
+    program_len: usize,
+) -> Option<Generated> {
+    assert!(ambiguous <= num_samples, "More ambiguous samples than samples");
+    let mut wanted = (0..num_samples).map(|i| i < ambiguous).collect::<Vec<_>>();
+    rng.shuffle(&mut wanted);
+
+    let mut samples = Vec::new();
+    for want_ambiguous in wanted {
+        let sample = (0..MAX_TRIES)
+            .map(|_| random_sample(rng, mapping))
+            .find(|sample| sample.is_ambiguous() == want_ambiguous)?;
+        samples.push(sample);
+    }
+    let program = (0..program_len).map(|_| random_instr(rng)).collect();
+
+    Some(Generated {
+        mapping: mapping.to_vec(),
+        samples,
+        program,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mapping;
+
+    #[test]
+    fn same_seed_same_input() {
+        let gen = |seed| {
+            let mut rng = Rng::new(seed);
+            let mapping = random_mapping(&mut rng);
+            generate(&mut rng, &mapping, 20, 10, 10).unwrap().to_string()
+        };
+        assert_eq!(gen(1), gen(1));
+        assert_ne!(gen(1), gen(2));
+    }
+
+    #[test]
+    fn controls_ambiguity() {
+        let mut rng = Rng::new(16);
+        let mapping = random_mapping(&mut rng);
+        for &ambiguous in [0, 37, 100].iter() {
+            let gen = generate(&mut rng, &mapping, 100, ambiguous, 0).unwrap();
+            assert_eq!(gen.samples.iter().filter(|s| s.is_ambiguous()).count(), ambiguous);
+            // And every sample fits its real opcode.
+            for sample in gen.samples.iter() {
+                assert!(sample.fitting().contains(&mapping[sample.instr[0]]));
+            }
+        }
+    }
+
+    #[test]
+    fn enough_samples_pin_down_mapping() {
+        let mut rng = Rng::new(2018);
+        let mapping = random_mapping(&mut rng);
+        let gen = generate(&mut rng, &mapping, 800, 400, 0).unwrap();
+        let samples = gen
+            .samples
+            .iter()
+            .map(|s| (s.instr[0], s.fitting().into_iter().collect()))
+            .collect::<Vec<_>>();
+        assert_eq!(mapping::analyse(&samples, 2).unique(), Some(&mapping[..]));
+    }
+
+    #[test]
+    fn formats_like_puzzle() {
+        let gen = Generated {
+            mapping: Opcode::ALL.to_vec(),
+            samples: vec![Sample {
+                before: vec![3, 2, 1, 1],
+                instr: [9, 2, 1, 2],
+                after: vec![3, 2, 2, 1],
+            }],
+            program: vec![[9, 3, 0, 0], [1, 0, 3, 0]],
+        };
+        assert_eq!(
+            gen.to_string(),
+            "Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]\n\n\n\n9 3 0 0\n1 0 3 0\n"
+        );
+        assert_eq!(gen.result(), 6);
+    }
+}
@@ -0,0 +1,41 @@
+// The hand-annotated listings in the repo should assemble to the
+// programs they annotate, and the programs should decompile.
+
+extern crate elfcode;
+
+use elfcode::{asm, decompile};
+use elfcode::Program;
+use std::fs;
+use std::path::Path;
+
+fn read(path: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(path);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("Couldn't read {}: {}", path.display(), e))
+}
+
+fn check_listing(listing: &str, program: &str) {
+    let expected = Program::parse(&read(program), 6).unwrap();
+    let assembled = asm::assemble(&read(listing), 6).unwrap_or_else(|e| panic!("{}: {}", listing, e));
+    assert_eq!(assembled, expected);
+    // And the disassembly goes back to the same thing.
+    assert_eq!(asm::assemble(&asm::disassemble(&expected), 6).unwrap(), expected);
+}
+
+#[test]
+fn day19_listing() {
+    check_listing("day19/input/input_decode.txt", "day19/input/input.txt");
+}
+
+#[test]
+fn day21_listing() {
+    check_listing("day21/input_decoded.txt", "day21/input/input.txt");
+}
+
+// Day 21's program is fully structured, so decompiles without gotos.
+#[test]
+fn day21_decompiles() {
+    let program = Program::parse(&read("day21/input/input.txt"), 6).unwrap();
+    let code = decompile::decompile(&program);
+    assert!(!code.contains("goto"), "{}", code);
+    assert!(code.contains("if (r4 == r0) {\n        break;\n    }"), "{}", code);
+}
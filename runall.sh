@@ -1,45 +1,5 @@
 #!/bin/sh
-cargo run --bin day01a < day01a/input/input.txt
-cargo run --bin day01b < day01a/input/input.txt
-cargo run --bin day02a < day02a/input/input.txt
-cargo run --bin day02b < day02a/input/input.txt
-cargo run --bin day03a < day03a/input/input.txt
-cargo run --bin day03b < day03a/input/input.txt
-cargo run --bin day04a < day04a/input/input.txt
-cargo run --bin day04b < day04a/input/input.txt
-cargo run --bin day05a < day05a/input/input.txt
-cargo run --bin day05b < day05a/input/input.txt
-cargo run --bin day06a < day06a/input/input.txt
-cargo run --bin day06b < day06a/input/input.txt
-cargo run --bin day07a < day07a/input/input.txt
-cargo run --bin day07b < day07a/input/input.txt
-cargo run --bin day08a < day08a/input/input.txt
-cargo run --bin day08b < day08a/input/input.txt
-cargo run --bin day09
-cargo run --bin day10 < day10/input/input.txt
-cargo run --bin day11a
-cargo run --bin day11b
-cargo run --bin day12a
-cargo run --bin day12b
-cargo run --bin day13a < day13a/input/input.txt
-cargo run --bin day13b < day13a/input/input.txt
-cargo run --bin day14a
-cargo run --bin day14b
-cargo run --bin day15a < day15a/input/input.txt
-cargo run --bin day15b < day15a/input/input.txt
-cargo run --bin day16a < day16a/input/input.txt
-cargo run --bin day16b < day16a/input/input.txt
-cargo run --bin day17 < day17/input/input.txt
-cargo run --bin day18a < day18a/input/input.txt
-cargo run --bin day18b < day18a/input/input.txt
-cargo run --bin day19 < day19/input/input.txt
-cargo run --bin day20a < day20a/input/input.txt
-cargo run --bin day20b < day20a/input/input.txt
-cargo run --bin day21 < day21/input/input.txt
-cargo run --bin day22a
-cargo run --bin day22b
-cargo run --bin day23a < day23a/input/input.txt
-cargo run --bin day23b < day23a/input/input.txt
-cargo run --bin day24a < day24a/input/input_expanded.txt
-cargo run --bin day24b < day24a/input/input_expanded.txt
-cargo run --bin day25a < day25a/input/input.txt
+# Each day can still be run on its own, e.g.
+#   cargo run --bin day01a < day01a/input/input.txt
+# but the aoc runner knows where all the inputs live.
+cargo run --release --bin aoc -- all